@@ -0,0 +1,36 @@
+use std::f64::consts::PI;
+
+use sdl2::pixels::Color;
+
+const CONVERSION_TICKS: u64 = 45;
+
+/// Flash and pulse played on an object right after it has been converted.
+#[derive(Debug, Clone, Copy)]
+pub struct ConversionAnimation {
+    started_at: u64,
+    color: Color,
+}
+
+impl ConversionAnimation {
+    pub fn new(started_at: u64, color: Color) -> ConversionAnimation {
+        ConversionAnimation { started_at, color }
+    }
+
+    fn progress(&self, tick: u64) -> f64 {
+        (tick.saturating_sub(self.started_at) as f64 / CONVERSION_TICKS as f64).min(1.0)
+    }
+
+    pub fn is_finished(&self, tick: u64) -> bool {
+        tick >= self.started_at + CONVERSION_TICKS
+    }
+
+    pub fn scale(&self, tick: u64) -> f64 {
+        1.0 + 0.4 * (PI * self.progress(tick)).sin()
+    }
+
+    pub fn flash_color(&self, tick: u64) -> Color {
+        let color = self.color;
+        let alpha = (200.0 * (1.0 - self.progress(tick))) as u8;
+        Color::RGBA(color.r, color.g, color.b, alpha)
+    }
+}
@@ -0,0 +1,243 @@
+use std::f64::consts::PI;
+use std::path::Path;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::camera::Camera;
+use crate::json::read_json;
+use crate::object::Object;
+
+const CIRCLE_SEGMENTS: usize = 96;
+
+/// Shape of the playing field. `Rect` is the whole screen and keeps the
+/// axis-aligned walls; the others reflect along their true boundary normal.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "lowercase")]
+pub enum Arena {
+    #[default]
+    Rect,
+    Circle {
+        x: f64,
+        y: f64,
+        radius: f64,
+    },
+    /// Convex polygon; the vertices may be listed in either winding order.
+    Polygon {
+        points: Vec<(f64, f64)>,
+    },
+}
+
+impl Arena {
+    /// Circle inscribed in a `width` x `height` screen.
+    pub fn circle(width: u32, height: u32) -> Arena {
+        Arena::Circle {
+            x: width as f64 / 2.0,
+            y: height as f64 / 2.0,
+            radius: width.min(height) as f64 / 2.0,
+        }
+    }
+
+    /// Regular polygon inscribed in a `width` x `height` screen.
+    pub fn regular(sides: usize, width: u32, height: u32) -> Arena {
+        assert!(sides >= 3, "a polygon needs at least 3 sides");
+        let (x, y) = (width as f64 / 2.0, height as f64 / 2.0);
+        let radius = width.min(height) as f64 / 2.0;
+        let points = (0..sides)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / sides as f64 - PI / 2.0;
+                (x + radius * angle.cos(), y + radius * angle.sin())
+            })
+            .collect();
+        Arena::Polygon { points }
+    }
+
+    pub fn load(path: &Path) -> Result<Arena, String> {
+        let arena: Arena = read_json(path)?;
+        if let Arena::Polygon { points } = &arena {
+            if points.len() < 3 {
+                return Err(format!(
+                    "{}: a polygon needs at least 3 points",
+                    path.display()
+                ));
+            }
+            if !is_convex(points) {
+                return Err(format!("{}: the polygon must be convex", path.display()));
+            }
+        }
+        Ok(arena)
+    }
+
+    /// Whether the object's bounding box lies entirely inside the arena.
+    pub fn contains(&self, object: &Object) -> bool {
+        self.penetration(object).is_none()
+    }
+
+    /// Inward unit normal of the wall the object is pushing through, if any.
+    pub fn wall_normal(&self, object: &Object) -> Option<(f64, f64)> {
+        self.penetration(object).map(|(_, normal)| normal)
+    }
+
+    fn penetration(&self, object: &Object) -> Option<(f64, (f64, f64))> {
+        let corners = corners(object);
+        match self {
+            Arena::Rect => None,
+            Arena::Circle { x, y, radius } => {
+                let (depth, (cx, cy)) = corners
+                    .iter()
+                    .map(|&(cx, cy)| ((cx - x).hypot(cy - y) - radius, (cx, cy)))
+                    .max_by(|a, b| a.0.total_cmp(&b.0))?;
+                if depth <= 0.0 {
+                    return None;
+                }
+                let (dx, dy) = (x - cx, y - cy);
+                let distance = dx.hypot(dy);
+                Some((depth, (dx / distance, dy / distance)))
+            }
+            Arena::Polygon { points } => {
+                let centroid = centroid(points);
+                let mut deepest: Option<(f64, (f64, f64))> = None;
+                for (i, &(ax, ay)) in points.iter().enumerate() {
+                    let (bx, by) = points[(i + 1) % points.len()];
+                    let length = (bx - ax).hypot(by - ay);
+                    if length <= f64::EPSILON {
+                        continue;
+                    }
+                    let mut normal = (-(by - ay) / length, (bx - ax) / length);
+                    if (centroid.0 - ax) * normal.0 + (centroid.1 - ay) * normal.1 < 0.0 {
+                        normal = (-normal.0, -normal.1);
+                    }
+                    let depth = corners
+                        .iter()
+                        .map(|&(cx, cy)| -((cx - ax) * normal.0 + (cy - ay) * normal.1))
+                        .fold(f64::MIN, f64::max);
+                    if depth > 0.0 && deepest.is_none_or(|(d, _)| depth > d) {
+                        deepest = Some((depth, normal));
+                    }
+                }
+                deepest
+            }
+        }
+    }
+
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera, color: Color) {
+        let points = match self {
+            Arena::Rect => return,
+            Arena::Circle { x, y, radius } => (0..=CIRCLE_SEGMENTS)
+                .map(|i| {
+                    let angle = 2.0 * PI * i as f64 / CIRCLE_SEGMENTS as f64;
+                    let (x, y) =
+                        camera.to_screen((x + radius * angle.cos(), y + radius * angle.sin()));
+                    Point::new(x as i32, y as i32)
+                })
+                .collect::<Vec<_>>(),
+            Arena::Polygon { points } => points
+                .iter()
+                .chain(points.first())
+                .map(|&point| {
+                    let (x, y) = camera.to_screen(point);
+                    Point::new(x as i32, y as i32)
+                })
+                .collect(),
+        };
+        canvas.set_draw_color(color);
+        canvas
+            .draw_lines(points.as_slice())
+            .expect("failed to draw arena");
+    }
+}
+
+fn corners(object: &Object) -> [(f64, f64); 4] {
+    let (left, right) = object.range_x();
+    let (top, bottom) = object.range_y();
+    [(left, top), (right, top), (right, bottom), (left, bottom)]
+}
+
+/// Every turn goes the same way and the turns add up to one full revolution,
+/// which also rules out self-intersecting outlines such as a pentagram.
+fn is_convex(points: &[(f64, f64)]) -> bool {
+    let mut sign = 0.0;
+    let mut turning = 0.0;
+    for i in 0..points.len() {
+        let (ax, ay) = points[i];
+        let (bx, by) = points[(i + 1) % points.len()];
+        let (cx, cy) = points[(i + 2) % points.len()];
+        let (ux, uy) = (bx - ax, by - ay);
+        let (vx, vy) = (cx - bx, cy - by);
+        let cross = ux * vy - uy * vx;
+        if cross.abs() <= f64::EPSILON {
+            continue;
+        }
+        if sign * cross < 0.0 {
+            return false;
+        }
+        sign = cross.signum();
+        turning += cross.atan2(ux * vx + uy * vy);
+    }
+    (turning.abs() - 2.0 * PI).abs() < 1e-6
+}
+
+fn centroid(points: &[(f64, f64)]) -> (f64, f64) {
+    let (sum_x, sum_y) = points
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    (sum_x / points.len() as f64, sum_y / points.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::square;
+
+    #[test]
+    fn convexity_accepts_either_winding() {
+        let square = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let reversed = square.iter().rev().copied().collect::<Vec<_>>();
+        assert!(is_convex(&square));
+        assert!(is_convex(&reversed));
+        assert!(is_convex(&[
+            (0.0, 0.0),
+            (5.0, 0.0),
+            (10.0, 0.0),
+            (5.0, 8.0)
+        ]));
+    }
+
+    #[test]
+    fn convexity_rejects_concave_and_self_intersecting_outlines() {
+        let arrow = [(0.0, 0.0), (10.0, 5.0), (0.0, 10.0), (3.0, 5.0)];
+        assert!(!is_convex(&arrow));
+        let star = (0..5)
+            .map(|i| {
+                let angle = 4.0 * PI * i as f64 / 5.0;
+                (angle.cos(), angle.sin())
+            })
+            .collect::<Vec<_>>();
+        assert!(!is_convex(&star));
+    }
+
+    #[test]
+    fn polygon_wall_normal_points_inward() {
+        let arena = Arena::Polygon {
+            points: vec![(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)],
+        };
+        assert!(arena.contains(&square(45.0, 45.0, 10)));
+        assert_eq!(
+            arena.wall_normal(&square(95.0, 45.0, 10)),
+            Some((-1.0, 0.0))
+        );
+        assert_eq!(arena.wall_normal(&square(45.0, -3.0, 10)), Some((0.0, 1.0)));
+    }
+
+    #[test]
+    fn circle_wall_normal_points_at_the_centre() {
+        let arena = Arena::circle(100, 100);
+        assert!(arena.contains(&square(45.0, 45.0, 10)));
+        assert!(!arena.contains(&square(0.0, 0.0, 10)));
+        let (nx, ny) = arena.wall_normal(&square(45.0, -5.0, 10)).unwrap();
+        assert!(ny > 0.99, "normal ({}, {})", nx, ny);
+        assert!((nx.hypot(ny) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,433 @@
+use std::env;
+use std::path::PathBuf;
+
+use crate::forces::Gravity;
+use crate::velocity::SpeedDistribution;
+
+#[derive(Debug, Clone)]
+pub struct Args {
+    snapshot: Option<PathBuf>,
+    assets_dir: Option<PathBuf>,
+    theme: Option<PathBuf>,
+    shapes: bool,
+    headless: bool,
+    trails: bool,
+    wrap: bool,
+    arena: Option<String>,
+    spawn: String,
+    objects: usize,
+    speed: Option<SpeedDistribution>,
+    species_speeds: Vec<(String, SpeedDistribution)>,
+    traits: Option<PathBuf>,
+    adopt_traits: bool,
+    obstacles: Option<PathBuf>,
+    drag: f64,
+    gravity: Option<Gravity>,
+    jitter: f64,
+    steering: bool,
+    behaviors: Vec<(String, String)>,
+    perception: f64,
+    max_acceleration: f64,
+    frames_dir: Option<PathBuf>,
+    record: Option<PathBuf>,
+    gif: Option<PathBuf>,
+    gif_from: f64,
+    gif_to: Option<f64>,
+    gif_max_bytes: Option<u64>,
+    frames: u64,
+    fps: u32,
+    resolution: (u32, u32),
+    world: (u32, u32),
+    minimap: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            snapshot: None,
+            assets_dir: None,
+            theme: None,
+            shapes: false,
+            headless: false,
+            trails: false,
+            wrap: false,
+            arena: None,
+            spawn: "corners".to_string(),
+            objects: 60,
+            speed: None,
+            species_speeds: Vec::new(),
+            traits: None,
+            adopt_traits: false,
+            obstacles: None,
+            drag: 0.0,
+            gravity: None,
+            jitter: 0.0,
+            steering: false,
+            behaviors: Vec::new(),
+            perception: 200.0,
+            max_acceleration: 0.02,
+            frames_dir: None,
+            record: None,
+            gif: None,
+            gif_from: 0.0,
+            gif_to: None,
+            gif_max_bytes: None,
+            frames: 300,
+            fps: 30,
+            resolution: (crate::SCREEN_WIDTH, crate::SCREEN_HEIGHT),
+            world: (crate::SCREEN_WIDTH, crate::SCREEN_HEIGHT),
+            minimap: false,
+        }
+    }
+}
+
+impl Args {
+    pub fn parse() -> Args {
+        let mut parsed = Args::default();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--snapshot" => {
+                    let path = args.next().expect("--snapshot requires a file path");
+                    parsed.snapshot = Some(PathBuf::from(path));
+                }
+                "--assets-dir" => {
+                    let path = args.next().expect("--assets-dir requires a directory");
+                    parsed.assets_dir = Some(PathBuf::from(path));
+                }
+                "--theme" => {
+                    let path = args.next().expect("--theme requires a theme directory");
+                    parsed.theme = Some(PathBuf::from(path));
+                }
+                "--shapes" => parsed.shapes = true,
+                "--headless" => parsed.headless = true,
+                "--trails" => parsed.trails = true,
+                "--wrap" => parsed.wrap = true,
+                "--arena" => {
+                    let arena = args
+                        .next()
+                        .expect("--arena requires rect, circle, hexagon or a file path");
+                    parsed.arena = Some(arena);
+                }
+                "--spawn" => {
+                    parsed.spawn = args
+                        .next()
+                        .expect("--spawn requires a layout name or a file path");
+                }
+                "--objects" => {
+                    let objects = args.next().expect("--objects requires a number");
+                    parsed.objects = objects.parse().expect("--objects must be a number");
+                }
+                "--speed" => {
+                    let speed = args.next().expect("--speed requires a distribution");
+                    parsed.speed = Some(SpeedDistribution::parse(&speed).expect(
+                        "--speed must look like fixed:1.1, uniform:0.5,1.5 or normal:1.1,0.2",
+                    ));
+                }
+                "--species-speed" => {
+                    let speed = args
+                        .next()
+                        .expect("--species-speed requires SPECIES=DISTRIBUTION");
+                    let (species, distribution) = speed
+                        .split_once('=')
+                        .expect("--species-speed must look like rock=normal:0.8,0.1");
+                    let distribution = SpeedDistribution::parse(distribution)
+                        .expect("--species-speed must look like rock=normal:0.8,0.1");
+                    parsed
+                        .species_speeds
+                        .push((species.to_string(), distribution));
+                }
+                "--traits" => {
+                    let path = args.next().expect("--traits requires a file path");
+                    parsed.traits = Some(PathBuf::from(path));
+                }
+                "--conversion" => {
+                    let policy = args
+                        .next()
+                        .expect("--conversion requires preserve or adopt");
+                    parsed.adopt_traits = match policy.as_str() {
+                        "preserve" => false,
+                        "adopt" => true,
+                        _ => panic!("--conversion must be preserve or adopt"),
+                    };
+                }
+                "--obstacles" => {
+                    let path = args.next().expect("--obstacles requires a file path");
+                    parsed.obstacles = Some(PathBuf::from(path));
+                }
+                "--drag" => {
+                    let drag = args.next().expect("--drag requires a number");
+                    parsed.drag = drag.parse().expect("--drag must be a number");
+                }
+                "--gravity" => {
+                    let gravity = args.next().expect("--gravity requires X,Y");
+                    let [x, y] = parse_numbers(&gravity).expect("--gravity must look like 0,0.05");
+                    parsed.gravity = Some(Gravity::Direction { x, y });
+                }
+                "--gravity-point" => {
+                    let gravity = args.next().expect("--gravity-point requires X,Y,STRENGTH");
+                    let [x, y, strength] = parse_numbers(&gravity)
+                        .expect("--gravity-point must look like 400,400,0.05");
+                    parsed.gravity = Some(Gravity::Point { x, y, strength });
+                }
+                "--jitter" => {
+                    let jitter = args.next().expect("--jitter requires a number");
+                    parsed.jitter = jitter.parse().expect("--jitter must be a number");
+                }
+                "--steering" => parsed.steering = true,
+                "--behavior" => {
+                    let behavior = args.next().expect("--behavior requires SPECIES=NAME");
+                    let (species, name) = behavior
+                        .split_once('=')
+                        .expect("--behavior must look like rock=swarm");
+                    parsed
+                        .behaviors
+                        .push((species.to_string(), name.to_string()));
+                }
+                "--perception" => {
+                    let radius = args.next().expect("--perception requires a radius");
+                    parsed.perception = radius.parse().expect("--perception must be a number");
+                }
+                "--max-acceleration" => {
+                    let acceleration = args.next().expect("--max-acceleration requires a number");
+                    parsed.max_acceleration = acceleration
+                        .parse()
+                        .expect("--max-acceleration must be a number");
+                }
+                "--frames-dir" => {
+                    let path = args.next().expect("--frames-dir requires a directory");
+                    parsed.frames_dir = Some(PathBuf::from(path));
+                }
+                "--record" => {
+                    let path = args.next().expect("--record requires a .y4m file path");
+                    parsed.record = Some(PathBuf::from(path));
+                }
+                "--gif" => {
+                    let path = args.next().expect("--gif requires a .gif file path");
+                    parsed.gif = Some(PathBuf::from(path));
+                }
+                "--gif-from" => {
+                    let seconds = args.next().expect("--gif-from requires seconds");
+                    parsed.gif_from = seconds.parse().expect("--gif-from must be a number");
+                }
+                "--gif-to" => {
+                    let seconds = args.next().expect("--gif-to requires seconds");
+                    parsed.gif_to = Some(seconds.parse().expect("--gif-to must be a number"));
+                }
+                "--gif-max-bytes" => {
+                    let bytes = args.next().expect("--gif-max-bytes requires a number");
+                    parsed.gif_max_bytes =
+                        Some(bytes.parse().expect("--gif-max-bytes must be a number"));
+                }
+                "--frames" => {
+                    let frames = args.next().expect("--frames requires a number");
+                    parsed.frames = frames.parse().expect("--frames must be a number");
+                }
+                "--fps" => {
+                    let fps = args.next().expect("--fps requires a number");
+                    parsed.fps = fps.parse().expect("--fps must be a number");
+                    assert!(parsed.fps > 0, "--fps must be greater than 0");
+                    assert!(
+                        parsed.fps <= crate::TICKS_PER_SECOND,
+                        "--fps must be at most {}",
+                        crate::TICKS_PER_SECOND
+                    );
+                }
+                "--resolution" => {
+                    let resolution = args.next().expect("--resolution requires WIDTHxHEIGHT");
+                    parsed.resolution = parse_resolution(&resolution)
+                        .expect("--resolution must look like 1920x1080");
+                }
+                "--world" => {
+                    let world = args.next().expect("--world requires WIDTHxHEIGHT");
+                    parsed.world =
+                        parse_resolution(&world).expect("--world must look like 10000x10000");
+                }
+                "--minimap" => parsed.minimap = true,
+                _ => panic!("unknown argument: {}", arg),
+            }
+        }
+        parsed
+    }
+
+    #[inline]
+    pub fn snapshot(&self) -> Option<&PathBuf> {
+        self.snapshot.as_ref()
+    }
+
+    #[inline]
+    pub fn assets_dir(&self) -> Option<&PathBuf> {
+        self.assets_dir.as_ref()
+    }
+
+    #[inline]
+    pub fn theme(&self) -> Option<&PathBuf> {
+        self.theme.as_ref()
+    }
+
+    #[inline]
+    pub fn shapes(&self) -> bool {
+        self.shapes
+    }
+
+    #[inline]
+    pub fn headless(&self) -> bool {
+        self.headless
+    }
+
+    #[inline]
+    pub fn trails(&self) -> bool {
+        self.trails
+    }
+
+    #[inline]
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    #[inline]
+    pub fn arena(&self) -> Option<&str> {
+        self.arena.as_deref()
+    }
+
+    /// Spawn layout name (corners, uniform, grid, rings, blobs) or a layout file.
+    #[inline]
+    pub fn spawn(&self) -> &str {
+        &self.spawn
+    }
+
+    #[inline]
+    pub fn objects(&self) -> usize {
+        self.objects
+    }
+
+    #[inline]
+    pub fn speed(&self) -> Option<SpeedDistribution> {
+        self.speed
+    }
+
+    #[inline]
+    pub fn species_speeds(&self) -> &[(String, SpeedDistribution)] {
+        &self.species_speeds
+    }
+
+    #[inline]
+    pub fn traits(&self) -> Option<&PathBuf> {
+        self.traits.as_ref()
+    }
+
+    /// Whether converted objects take on their new species' traits.
+    #[inline]
+    pub fn adopt_traits(&self) -> bool {
+        self.adopt_traits
+    }
+
+    #[inline]
+    pub fn obstacles(&self) -> Option<&PathBuf> {
+        self.obstacles.as_ref()
+    }
+
+    #[inline]
+    pub fn drag(&self) -> f64 {
+        self.drag
+    }
+
+    #[inline]
+    pub fn gravity(&self) -> Option<Gravity> {
+        self.gravity
+    }
+
+    #[inline]
+    pub fn jitter(&self) -> f64 {
+        self.jitter
+    }
+
+    #[inline]
+    pub fn steering(&self) -> bool {
+        self.steering
+    }
+
+    #[inline]
+    pub fn behaviors(&self) -> &[(String, String)] {
+        &self.behaviors
+    }
+
+    #[inline]
+    pub fn perception(&self) -> f64 {
+        self.perception
+    }
+
+    #[inline]
+    pub fn max_acceleration(&self) -> f64 {
+        self.max_acceleration
+    }
+
+    #[inline]
+    pub fn frames_dir(&self) -> Option<&PathBuf> {
+        self.frames_dir.as_ref()
+    }
+
+    #[inline]
+    pub fn record(&self) -> Option<&PathBuf> {
+        self.record.as_ref()
+    }
+
+    #[inline]
+    pub fn gif(&self) -> Option<&PathBuf> {
+        self.gif.as_ref()
+    }
+
+    #[inline]
+    pub fn gif_from(&self) -> f64 {
+        self.gif_from
+    }
+
+    #[inline]
+    pub fn gif_to(&self) -> Option<f64> {
+        self.gif_to
+    }
+
+    #[inline]
+    pub fn gif_max_bytes(&self) -> Option<u64> {
+        self.gif_max_bytes
+    }
+
+    #[inline]
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    #[inline]
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    #[inline]
+    pub fn resolution(&self) -> (u32, u32) {
+        self.resolution
+    }
+
+    /// Arena size in world units, independent of the window.
+    #[inline]
+    pub fn world(&self) -> (u32, u32) {
+        self.world
+    }
+
+    #[inline]
+    pub fn minimap(&self) -> bool {
+        self.minimap
+    }
+}
+
+fn parse_resolution(resolution: &str) -> Option<(u32, u32)> {
+    let (width, height) = resolution.split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+fn parse_numbers<const N: usize>(numbers: &str) -> Option<[f64; N]> {
+    let numbers = numbers
+        .split(',')
+        .map(|number| number.trim().parse().ok())
+        .collect::<Option<Vec<f64>>>()?;
+    numbers.try_into().ok()
+}
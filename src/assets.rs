@@ -0,0 +1,176 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use sdl2::image::{ImageRWops, LoadSurface};
+use sdl2::render::{Texture, TextureCreator};
+use sdl2::rwops::RWops;
+use sdl2::surface::Surface;
+use sdl2::ttf::{Font, Sdl2TtfContext};
+
+use crate::error::StartupError;
+use crate::theme::Theme;
+
+pub const FONT_FILE: &str = "SourceCodePro-Bold.ttf";
+pub const ROCK_SPRITE: &str = "rock.png";
+pub const PAPER_SPRITE: &str = "paper.png";
+pub const SCISSORS_SPRITE: &str = "scissors.png";
+
+const EMBEDDED: [(&str, &[u8]); 4] = [
+    (
+        FONT_FILE,
+        include_bytes!("../assets/SourceCodePro-Bold.ttf"),
+    ),
+    (ROCK_SPRITE, include_bytes!("../assets/rock.png")),
+    (PAPER_SPRITE, include_bytes!("../assets/paper.png")),
+    (SCISSORS_SPRITE, include_bytes!("../assets/scissors.png")),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureHandle(usize);
+
+/// Where asset files are read from: the defaults compiled into the binary, or
+/// a directory given with `--assets-dir` or `--theme`.
+#[derive(Debug, Clone)]
+pub enum AssetSource {
+    Embedded,
+    Dir(PathBuf),
+}
+
+impl AssetSource {
+    pub fn new(dir: Option<&PathBuf>) -> AssetSource {
+        match dir {
+            Some(dir) => AssetSource::Dir(dir.clone()),
+            None => AssetSource::Embedded,
+        }
+    }
+
+    pub fn load_surface(&self, name: &str) -> Result<Surface<'static>, StartupError> {
+        match self.locate(name)? {
+            Some(path) => {
+                Surface::from_file(&path).map_err(|message| StartupError::Texture { path, message })
+            }
+            None => RWops::from_bytes(embedded(name)?)
+                .and_then(|rwops| rwops.load())
+                .map_err(|message| StartupError::Texture {
+                    path: PathBuf::from(name),
+                    message,
+                }),
+        }
+    }
+
+    pub fn load_font<'ttf_module>(
+        &self,
+        ttf_context: &'ttf_module Sdl2TtfContext,
+        name: &str,
+        point_size: u16,
+    ) -> Result<Font<'ttf_module, 'static>, StartupError> {
+        match self.locate(name)? {
+            Some(path) => ttf_context
+                .load_font(&path, point_size)
+                .map_err(|message| StartupError::Font { path, message }),
+            None => RWops::from_bytes(embedded(name)?)
+                .and_then(|rwops| ttf_context.load_font_from_rwops(rwops, point_size))
+                .map_err(|message| StartupError::Font {
+                    path: PathBuf::from(name),
+                    message,
+                }),
+        }
+    }
+
+    /// Returns the file to load, or `None` when the embedded copy should be
+    /// used. A directory only needs to contain the files it overrides.
+    fn locate(&self, name: &str) -> Result<Option<PathBuf>, StartupError> {
+        match self {
+            AssetSource::Embedded => Ok(None),
+            AssetSource::Dir(dir) => match resolve(&dir.join(name)) {
+                Ok(path) => Ok(Some(path)),
+                Err(_) if embedded(name).is_ok() => Ok(None),
+                Err(e) => Err(e),
+            },
+        }
+    }
+}
+
+/// Owns every SDL resource used for drawing. The simulation only refers to
+/// textures by handle, so it stays free of SDL lifetimes.
+pub struct Assets<'r, 'ttf_module, 'rwops> {
+    textures: Vec<Texture<'r>>,
+    font: Font<'ttf_module, 'rwops>,
+    theme: Theme,
+}
+
+impl<'r, 'ttf_module, 'rwops> Assets<'r, 'ttf_module, 'rwops> {
+    pub fn new(font: Font<'ttf_module, 'rwops>, theme: Theme) -> Assets<'r, 'ttf_module, 'rwops> {
+        Assets {
+            textures: Vec::new(),
+            font,
+            theme,
+        }
+    }
+
+    pub fn load_texture<T>(
+        &mut self,
+        texture_creator: &'r TextureCreator<T>,
+        source: &AssetSource,
+        name: &str,
+    ) -> Result<TextureHandle, StartupError> {
+        let surface = source.load_surface(name)?;
+        let texture = texture_creator
+            .create_texture_from_surface(&surface)
+            .map_err(|e| StartupError::Texture {
+                path: PathBuf::from(name),
+                message: e.to_string(),
+            })?;
+        self.textures.push(texture);
+        Ok(TextureHandle(self.textures.len() - 1))
+    }
+
+    #[inline]
+    pub fn texture(&self, handle: TextureHandle) -> &Texture<'r> {
+        &self.textures[handle.0]
+    }
+
+    #[inline]
+    pub fn font(&self) -> &Font<'ttf_module, 'rwops> {
+        &self.font
+    }
+
+    #[inline]
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+}
+
+fn embedded(name: &str) -> Result<&'static [u8], StartupError> {
+    EMBEDDED
+        .iter()
+        .find(|(file, _)| *file == name)
+        .map(|(_, bytes)| *bytes)
+        .ok_or_else(|| StartupError::AssetNotFound {
+            path: PathBuf::from(name),
+            searched: vec![PathBuf::from("<embedded>")],
+        })
+}
+
+/// Finds an asset relative to the working directory, falling back to the
+/// directory containing the executable.
+fn resolve(path: &Path) -> Result<PathBuf, StartupError> {
+    if path.exists() {
+        return Ok(path.to_path_buf());
+    }
+    let mut searched = vec![path.to_path_buf()];
+    if let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        let candidate = dir.join(path);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+        searched.push(candidate);
+    }
+    Err(StartupError::AssetNotFound {
+        path: path.to_path_buf(),
+        searched,
+    })
+}
@@ -0,0 +1,138 @@
+use uuid::Uuid;
+
+use crate::grid::SpatialGrid;
+use crate::screen_object::ScreenObject;
+use crate::species::SpeciesRegistry;
+
+/// Decides how an object of one species accelerates each tick. Register one
+/// per species with `Screen::set_behavior`.
+pub trait Behavior {
+    fn acceleration(&self, object: &ScreenObject, neighbors: &Neighbors) -> (f64, f64);
+
+    /// The largest radius this behaviour passes to `Neighbors`. The screen
+    /// sizes its neighbour grid to it; larger queries fall back to a full scan.
+    fn perception(&self) -> f64;
+}
+
+/// Read-only view of the other objects, as seen from one object.
+pub struct Neighbors<'a> {
+    id: Uuid,
+    center: (f64, f64),
+    objects: &'a [(Uuid, ScreenObject)],
+    /// Indices into `objects`, bucketed by position.
+    grid: &'a SpatialGrid<usize>,
+    species: &'a SpeciesRegistry,
+}
+
+impl<'a> Neighbors<'a> {
+    pub fn new(
+        id: Uuid,
+        object: &ScreenObject,
+        objects: &'a [(Uuid, ScreenObject)],
+        grid: &'a SpatialGrid<usize>,
+        species: &'a SpeciesRegistry,
+    ) -> Neighbors<'a> {
+        Neighbors {
+            id,
+            center: object.object().center(),
+            objects,
+            grid,
+            species,
+        }
+    }
+
+    #[inline]
+    pub fn species(&self) -> &SpeciesRegistry {
+        self.species
+    }
+
+    /// Objects whose centre lies within `radius`, with their offset from this object.
+    pub fn within(&self, radius: f64) -> impl Iterator<Item = (&'a ScreenObject, (f64, f64))> + '_ {
+        let objects = self.objects;
+        let in_grid = radius <= self.grid.cell_size();
+        let near = in_grid.then(|| {
+            self.grid
+                .nearby(self.center)
+                .map(move |&index| &objects[index])
+        });
+        let all = (!in_grid).then(|| objects.iter());
+        near.into_iter()
+            .flatten()
+            .chain(all.into_iter().flatten())
+            .filter(move |(id, _)| *id != self.id)
+            .filter_map(move |(_, other)| {
+                let (x, y) = other.object().center();
+                let offset = (x - self.center.0, y - self.center.1);
+                (offset.0.hypot(offset.1) <= radius).then_some((other, offset))
+            })
+    }
+
+    /// Offset to the closest object within `radius` that matches `predicate`.
+    pub fn nearest(
+        &self,
+        radius: f64,
+        predicate: impl Fn(&ScreenObject) -> bool,
+    ) -> Option<(f64, f64)> {
+        self.within(radius)
+            .filter(|(other, _)| predicate(other))
+            .map(|(_, offset)| offset)
+            .min_by(|a, b| a.0.hypot(a.1).total_cmp(&b.0.hypot(b.1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::Object;
+    use crate::position::Position;
+    use crate::species::SpeciesId;
+    use crate::velocity::Velocity;
+
+    fn objects() -> Vec<(Uuid, ScreenObject)> {
+        (0..40)
+            .map(|i| {
+                let (x, y) = ((i * 37 % 780) as f64, (i * 53 % 580) as f64);
+                let object = Object::new(
+                    Position::new(x, y, 790.0, 590.0),
+                    10,
+                    10,
+                    1.0,
+                    Velocity::new(0.0, 0.0),
+                );
+                (Uuid::new_v4(), ScreenObject::new(SpeciesId::ROCK, object))
+            })
+            .collect()
+    }
+
+    fn grid(objects: &[(Uuid, ScreenObject)], cell_size: f64) -> SpatialGrid<usize> {
+        let mut grid = SpatialGrid::new(cell_size, 800.0, 600.0, None);
+        for (index, (_, object)) in objects.iter().enumerate() {
+            grid.insert(object.object().center(), index);
+        }
+        grid
+    }
+
+    fn offsets(neighbors: &Neighbors, radius: f64) -> Vec<(f64, f64)> {
+        let mut offsets = neighbors
+            .within(radius)
+            .map(|(_, offset)| offset)
+            .collect::<Vec<_>>();
+        offsets.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        offsets
+    }
+
+    #[test]
+    fn grid_finds_the_same_neighbors_as_a_full_scan() {
+        let objects = objects();
+        let species = SpeciesRegistry::default();
+        let coarse = grid(&objects, 1000.0);
+        let fine = grid(&objects, 100.0);
+        for (id, object) in objects.iter() {
+            let everything = Neighbors::new(*id, object, &objects, &coarse, &species);
+            let nearby = Neighbors::new(*id, object, &objects, &fine, &species);
+            for radius in [50.0, 100.0, 300.0] {
+                assert_eq!(offsets(&nearby, radius), offsets(&everything, radius));
+            }
+        }
+    }
+}
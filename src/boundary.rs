@@ -0,0 +1,9 @@
+/// What happens at the edge of the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Boundary {
+    /// Objects bounce off the edges.
+    #[default]
+    Walls,
+    /// Objects leaving one edge reappear on the opposite side.
+    Wrap,
+}
@@ -0,0 +1,175 @@
+use sdl2::rect::Rect;
+use uuid::Uuid;
+
+use crate::screen::Screen;
+
+const MIN_ZOOM: f64 = 0.1;
+const MAX_ZOOM: f64 = 16.0;
+
+/// What the camera keeps centred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Follow {
+    Free,
+    Object(Uuid),
+    /// Centroid of the smallest species still alive.
+    Survivors,
+}
+
+/// Maps world coordinates to the view. `x`/`y` is the world point shown in
+/// the middle of the view.
+#[derive(Debug, Clone)]
+pub struct Camera {
+    x: f64,
+    y: f64,
+    zoom: f64,
+    /// Never above the zoom that fits the whole world, so it can always be
+    /// zoomed back out to the starting view.
+    min_zoom: f64,
+    view_width: f64,
+    view_height: f64,
+    follow: Follow,
+}
+
+impl Camera {
+    /// Centred on the world and zoomed out just enough to show all of it.
+    pub fn new(world_width: u32, world_height: u32, view_width: u32, view_height: u32) -> Camera {
+        let mut camera = Camera::fit(world_width, world_height, view_width, view_height);
+        camera.zoom = camera.zoom.min(1.0);
+        camera.min_zoom = camera.zoom.min(MIN_ZOOM);
+        camera
+    }
+
+    /// Like `new`, but also zooms in on a world smaller than the view so it
+    /// fills the view along one axis and is centred along the other.
+    pub fn fit(world_width: u32, world_height: u32, view_width: u32, view_height: u32) -> Camera {
+        let zoom =
+            (view_width as f64 / world_width as f64).min(view_height as f64 / world_height as f64);
+        Camera {
+            x: world_width as f64 / 2.0,
+            y: world_height as f64 / 2.0,
+            zoom,
+            min_zoom: zoom.min(MIN_ZOOM),
+            view_width: view_width as f64,
+            view_height: view_height as f64,
+            follow: Follow::Free,
+        }
+    }
+
+    #[inline]
+    pub fn view_size(&self) -> (f64, f64) {
+        (self.view_width, self.view_height)
+    }
+
+    pub fn set_follow(&mut self, follow: Follow) {
+        self.follow = follow;
+    }
+
+    /// Moves the view by a distance in view pixels and stops following.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.x += dx / self.zoom;
+        self.y += dy / self.zoom;
+        self.follow = Follow::Free;
+    }
+
+    /// Zooms by `factor`, keeping the world point under `(view_x, view_y)` still.
+    pub fn zoom_at(&mut self, factor: f64, (view_x, view_y): (f64, f64)) {
+        let (world_x, world_y) = self.to_world((view_x, view_y));
+        self.zoom = (self.zoom * factor).clamp(self.min_zoom, MAX_ZOOM);
+        let (after_x, after_y) = self.to_world((view_x, view_y));
+        self.x += world_x - after_x;
+        self.y += world_y - after_y;
+    }
+
+    /// Re-centres on the followed target.
+    pub fn update(&mut self, screen: &Screen) {
+        let target = match self.follow {
+            Follow::Free => None,
+            Follow::Object(id) => screen.object_center(id),
+            Follow::Survivors => screen.survivors_center(),
+        };
+        if let Some((x, y)) = target {
+            self.x = x;
+            self.y = y;
+        }
+    }
+
+    pub fn to_screen(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            (x - self.x) * self.zoom + self.view_width / 2.0,
+            (y - self.y) * self.zoom + self.view_height / 2.0,
+        )
+    }
+
+    pub fn to_world(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            (x - self.view_width / 2.0) / self.zoom + self.x,
+            (y - self.view_height / 2.0) / self.zoom + self.y,
+        )
+    }
+
+    /// Maps a world-space box to the view. Only the resulting edges are
+    /// rounded, so zoomed-in objects move smoothly instead of in whole world
+    /// pixels.
+    pub fn to_screen_rect(&self, (x, y, width, height): (f64, f64, f64, f64)) -> Rect {
+        let (left, top) = self.to_screen((x, y));
+        let (right, bottom) = self.to_screen((x + width, y + height));
+        let (left, top) = (left.round(), top.round());
+        Rect::new(
+            left as i32,
+            top as i32,
+            ((right.round() - left) as u32).max(1),
+            ((bottom.round() - top) as u32).max(1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_zoom_back_out_to_a_world_larger_than_the_minimum_zoom() {
+        let mut camera = Camera::new(10000, 10000, 800, 600);
+        let fit = camera.zoom;
+        assert_eq!(fit, 0.06);
+
+        camera.zoom_at(0.5, (400.0, 300.0));
+        assert_eq!(camera.zoom, fit);
+        camera.zoom_at(4.0, (400.0, 300.0));
+        camera.zoom_at(0.125, (400.0, 300.0));
+        assert_eq!(camera.zoom, fit);
+    }
+
+    #[test]
+    fn fitting_a_wide_view_keeps_the_world_square_and_centred() {
+        let camera = Camera::fit(800, 800, 1920, 1080);
+        let world = camera.to_screen_rect((0.0, 0.0, 800.0, 800.0));
+        assert_eq!((world.width(), world.height()), (1080, 1080));
+        assert_eq!((world.x(), world.y()), (420, 0));
+        assert_eq!(Camera::new(800, 800, 1920, 1080).zoom, 1.0);
+    }
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_cursor() {
+        let mut camera = Camera::new(800, 600, 800, 600);
+        let before = camera.to_world((100.0, 50.0));
+        camera.zoom_at(2.0, (100.0, 50.0));
+        let after = camera.to_world((100.0, 50.0));
+        assert!((before.0 - after.0).abs() < 1e-9 && (before.1 - after.1).abs() < 1e-9);
+        assert_eq!(
+            camera.to_screen(camera.to_world((10.0, 20.0))),
+            (10.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn zoomed_rects_move_by_fractions_of_a_world_pixel() {
+        let mut camera = Camera::new(800, 600, 800, 600);
+        camera.zoom_at(MAX_ZOOM, (400.0, 300.0));
+        let before = camera.to_screen_rect((400.0, 300.0, 25.0, 25.0));
+        let after = camera.to_screen_rect((400.25, 300.5, 25.0, 25.0));
+        assert_eq!(after.x() - before.x(), 4);
+        assert_eq!(after.y() - before.y(), 8);
+        assert_eq!((before.width(), before.height()), (400, 400));
+    }
+}
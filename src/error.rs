@@ -0,0 +1,74 @@
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum StartupError {
+    Sdl(String),
+    AssetNotFound {
+        path: PathBuf,
+        searched: Vec<PathBuf>,
+    },
+    Font {
+        path: PathBuf,
+        message: String,
+    },
+    Texture {
+        path: PathBuf,
+        message: String,
+    },
+    Snapshot(String),
+    Theme(String),
+    Obstacles(String),
+    Arena(String),
+    Spawn(String),
+    Traits(String),
+    Speed(String),
+    Forces(String),
+    Behavior(String),
+    Steering(String),
+    Output(String),
+    NoOutput,
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartupError::Sdl(message) => write!(f, "failed to initialise SDL: {}", message),
+            StartupError::AssetNotFound { path, searched } => {
+                write!(f, "asset {} not found (looked in ", path.display())?;
+                for (i, candidate) in searched.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", candidate.display())?;
+                }
+                write!(f, ")")
+            }
+            StartupError::Font { path, message } => {
+                write!(f, "failed to load font {}: {}", path.display(), message)
+            }
+            StartupError::Texture { path, message } => {
+                write!(f, "failed to load sprite {}: {}", path.display(), message)
+            }
+            StartupError::Snapshot(message) => write!(f, "failed to load snapshot {}", message),
+            StartupError::Theme(message) => write!(f, "failed to load theme {}", message),
+            StartupError::Obstacles(message) => {
+                write!(f, "failed to load obstacles {}", message)
+            }
+            StartupError::Arena(message) => write!(f, "invalid arena: {}", message),
+            StartupError::Spawn(message) => write!(f, "failed to spawn objects {}", message),
+            StartupError::Traits(message) => write!(f, "failed to load traits {}", message),
+            StartupError::Speed(message) => write!(f, "invalid --species-speed: {}", message),
+            StartupError::Forces(message) => write!(f, "invalid forces: {}", message),
+            StartupError::Behavior(message) => write!(f, "invalid --behavior: {}", message),
+            StartupError::Steering(message) => write!(f, "invalid steering: {}", message),
+            StartupError::Output(message) => write!(f, "failed to open output {}", message),
+            StartupError::NoOutput => write!(
+                f,
+                "headless mode needs an output such as --frames-dir, --record or --gif"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StartupError {}
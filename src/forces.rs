@@ -0,0 +1,81 @@
+use rand::Rng;
+
+use crate::object::Object;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gravity {
+    /// Constant acceleration in one direction, in pixels per tick².
+    Direction { x: f64, y: f64 },
+    /// Constant-magnitude pull toward a point in the arena.
+    Point { x: f64, y: f64, strength: f64 },
+}
+
+/// Global forces applied to every object before it moves.
+#[derive(Debug, Clone, Default)]
+pub struct Forces {
+    drag: f64,
+    gravity: Option<Gravity>,
+    jitter: f64,
+}
+
+impl Forces {
+    pub fn new(drag: f64, gravity: Option<Gravity>, jitter: f64) -> Result<Forces, String> {
+        if !(0.0..=1.0).contains(&drag) {
+            return Err(format!("drag must be between 0 and 1, got {}", drag));
+        }
+        if !(jitter >= 0.0 && jitter.is_finite()) {
+            return Err(format!(
+                "jitter must be a non-negative number, got {}",
+                jitter
+            ));
+        }
+        Ok(Forces {
+            drag,
+            gravity,
+            jitter,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drag == 0.0 && self.gravity.is_none() && self.jitter == 0.0
+    }
+
+    pub fn apply(&self, object: &mut Object, rng: &mut impl Rng) {
+        let (center_x, center_y) = object.center();
+        let velocity = object.velocity_mut();
+        velocity.scale(1.0 - self.drag);
+        match self.gravity {
+            Some(Gravity::Direction { x, y }) => velocity.accelerate(x, y),
+            Some(Gravity::Point { x, y, strength }) => {
+                let (dx, dy) = (x - center_x, y - center_y);
+                let distance = dx.hypot(dy);
+                if distance > f64::EPSILON {
+                    velocity.accelerate(dx / distance * strength, dy / distance * strength);
+                }
+            }
+            None => {}
+        }
+        // ブラウン運動のような揺らぎ
+        if self.jitter > 0.0 {
+            velocity.accelerate(
+                rng.gen_range(-self.jitter..=self.jitter),
+                rng.gen_range(-self.jitter..=self.jitter),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_drag_and_jitter_out_of_range() {
+        assert!(Forces::new(0.5, None, 0.1).is_ok());
+        assert!(Forces::new(1.5, None, 0.0).is_err());
+        assert!(Forces::new(-0.1, None, 0.0).is_err());
+        assert!(Forces::new(f64::NAN, None, 0.0).is_err());
+        assert!(Forces::new(0.0, None, -1.0).is_err());
+        assert!(Forces::new(0.0, None, f64::INFINITY).is_err());
+    }
+}
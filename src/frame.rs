@@ -0,0 +1,123 @@
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::{Canvas, RenderTarget};
+
+/// A captured frame as tightly packed RGB24 pixels.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Frame {
+    pub fn read<T: RenderTarget>(canvas: &Canvas<T>) -> Result<Frame, String> {
+        let (width, height) = canvas.output_size()?;
+        let pixels = canvas.read_pixels(None, PixelFormatEnum::RGB24)?;
+        Ok(Frame {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
+
+pub trait FrameSink {
+    fn write_frame(&mut self, frame: &Frame) -> Result<(), String>;
+
+    fn finish(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Captures `fps` of every `ticks_per_second` ticks into all registered
+/// sinks, spread as evenly as whole ticks allow.
+pub struct Recorder {
+    sinks: Vec<Box<dyn FrameSink>>,
+    fps: u64,
+    ticks_per_second: u64,
+}
+
+impl Recorder {
+    pub fn new(fps: u64, ticks_per_second: u64) -> Recorder {
+        assert!(fps > 0, "fps must be greater than 0");
+        assert!(fps <= ticks_per_second, "fps must not exceed the tick rate");
+        Recorder {
+            sinks: Vec::new(),
+            fps,
+            ticks_per_second,
+        }
+    }
+
+    pub fn add_sink(&mut self, sink: Box<dyn FrameSink>) {
+        self.sinks.push(sink);
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    pub fn is_due(&self, tick: u64) -> bool {
+        !self.sinks.is_empty() && self.is_capture_tick(tick)
+    }
+
+    /// 端数を繰り越すので、150 tick に 60 fps なら 2 tick と 3 tick の間隔が交互になる
+    fn is_capture_tick(&self, tick: u64) -> bool {
+        (tick * self.fps) % self.ticks_per_second < self.fps
+    }
+
+    pub fn capture<T: RenderTarget>(&mut self, canvas: &Canvas<T>) -> Result<(), String> {
+        let frame = Frame::read(canvas)?;
+        for sink in self.sinks.iter_mut() {
+            sink.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> Result<(), String> {
+        for sink in self.sinks.iter_mut() {
+            sink.finish()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn captures(fps: u64, ticks: u64) -> Vec<u64> {
+        let recorder = Recorder::new(fps, 150);
+        (0..ticks)
+            .filter(|tick| recorder.is_capture_tick(*tick))
+            .collect()
+    }
+
+    #[test]
+    fn captures_divisors_at_a_fixed_interval() {
+        assert_eq!(captures(30, 30), vec![0, 5, 10, 15, 20, 25]);
+    }
+
+    #[test]
+    fn captures_the_requested_rate_when_it_does_not_divide_the_tick_rate() {
+        for fps in [24, 40, 60, 149, 150] {
+            assert_eq!(captures(fps, 150 * 4).len() as u64, fps * 4, "fps {}", fps);
+        }
+        assert_eq!(captures(60, 10), vec![0, 3, 5, 8]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use gif::{Encoder, Repeat};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+
+use crate::frame::{Frame, FrameSink};
+
+const LOOKUP_BITS: u32 = 5;
+const LOOKUP_SIZE: usize = 1 << (LOOKUP_BITS * 3);
+
+/// A 256 colour palette built from the species sprites, with a lookup table
+/// mapping every 15-bit colour to its nearest palette entry.
+pub struct Palette {
+    colors: Vec<[u8; 3]>,
+    lookup: Vec<u8>,
+}
+
+impl Palette {
+    pub fn from_sprites(sprites: &[Surface], reserved: &[[u8; 3]]) -> Result<Palette, String> {
+        // (count, sum_r, sum_g, sum_b) per 15-bit bucket
+        let mut buckets = vec![(0u64, [0u64; 3]); LOOKUP_SIZE];
+        for sprite in sprites {
+            let surface = sprite.convert_format(PixelFormatEnum::RGBA32)?;
+            let (width, pitch) = (surface.width() as usize, surface.pitch() as usize);
+            surface.with_lock(|pixels| {
+                for row in pixels.chunks_exact(pitch) {
+                    for rgba in row[..width * 4].chunks_exact(4) {
+                        // 透明なピクセルは背景色になるので数えない
+                        if rgba[3] < 128 {
+                            continue;
+                        }
+                        let bucket = &mut buckets[lookup_key(rgba[0], rgba[1], rgba[2])];
+                        bucket.0 += 1;
+                        for (sum, value) in bucket.1.iter_mut().zip(rgba) {
+                            *sum += *value as u64;
+                        }
+                    }
+                }
+            });
+        }
+
+        let mut popular = buckets
+            .into_iter()
+            .filter(|(count, _)| *count > 0)
+            .collect::<Vec<_>>();
+        popular.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+
+        let mut colors = reserved.to_vec();
+        for (count, sum) in popular.into_iter().take(256 - reserved.len()) {
+            colors.push([
+                (sum[0] / count) as u8,
+                (sum[1] / count) as u8,
+                (sum[2] / count) as u8,
+            ]);
+        }
+
+        let lookup = (0..LOOKUP_SIZE)
+            .map(|key| {
+                let center = lookup_center(key);
+                colors
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, color)| distance(color, &center))
+                    .map(|(index, _)| index as u8)
+                    .unwrap()
+            })
+            .collect();
+        Ok(Palette { colors, lookup })
+    }
+
+    pub fn quantize(&self, frame: &Frame) -> Vec<u8> {
+        frame
+            .pixels()
+            .chunks_exact(3)
+            .map(|rgb| self.lookup[lookup_key(rgb[0], rgb[1], rgb[2])])
+            .collect()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.colors.iter().flatten().copied().collect()
+    }
+}
+
+fn lookup_key(r: u8, g: u8, b: u8) -> usize {
+    let shift = 8 - LOOKUP_BITS;
+    ((r >> shift) as usize) << (LOOKUP_BITS * 2)
+        | ((g >> shift) as usize) << LOOKUP_BITS
+        | (b >> shift) as usize
+}
+
+fn lookup_center(key: usize) -> [u8; 3] {
+    let (shift, mask) = (8 - LOOKUP_BITS, (1 << LOOKUP_BITS) - 1);
+    let half = 1 << (shift - 1);
+    [
+        ((key >> (LOOKUP_BITS * 2) & mask) << shift | half) as u8,
+        ((key >> LOOKUP_BITS & mask) << shift | half) as u8,
+        ((key & mask) << shift | half) as u8,
+    ]
+}
+
+fn distance(a: &[u8; 3], b: &[u8; 3]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| (*a as i32 - *b as i32).pow(2) as u32)
+        .sum()
+}
+
+struct CountingWriter<W> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct PendingFrame {
+    index: u64,
+    width: u16,
+    height: u16,
+    indices: Vec<u8>,
+}
+
+/// Encodes captured frames into an animated GIF.
+///
+/// Only frames whose capture index falls into `window` are kept. When a size
+/// budget is given, frames are skipped so that the projected file size for the
+/// rest of the window stays within it; the skipped time is folded into the
+/// delay of the previous frame so playback speed is preserved. A window ending
+/// at `u64::MAX` is open-ended, so frames are kept until the budget runs out.
+pub struct GifWriter {
+    path: PathBuf,
+    palette: Palette,
+    frame_delay: f64,
+    window: Range<u64>,
+    max_bytes: Option<u64>,
+    writer: Option<CountingWriter<BufWriter<File>>>,
+    encoder: Option<Encoder<CountingWriter<BufWriter<File>>>>,
+    index: u64,
+    kept: u64,
+    skip: u64,
+    pending: Option<PendingFrame>,
+}
+
+impl GifWriter {
+    pub fn new(
+        path: PathBuf,
+        palette: Palette,
+        frame_delay: f64,
+        window: Range<u64>,
+        max_bytes: Option<u64>,
+    ) -> Result<GifWriter, String> {
+        let file = File::create(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(GifWriter {
+            path,
+            palette,
+            frame_delay,
+            window,
+            max_bytes,
+            writer: Some(CountingWriter {
+                inner: BufWriter::new(file),
+                written: 0,
+            }),
+            encoder: None,
+            index: 0,
+            kept: 0,
+            skip: 1,
+            pending: None,
+        })
+    }
+
+    fn encoder(
+        &mut self,
+        width: u16,
+        height: u16,
+    ) -> Result<&mut Encoder<CountingWriter<BufWriter<File>>>, String> {
+        if self.encoder.is_none() {
+            let writer = self.writer.take().unwrap();
+            let mut encoder = Encoder::new(writer, width, height, &self.palette.to_bytes())
+                .map_err(|e| e.to_string())?;
+            encoder
+                .set_repeat(Repeat::Infinite)
+                .map_err(|e| e.to_string())?;
+            self.encoder = Some(encoder);
+        }
+        Ok(self.encoder.as_mut().unwrap())
+    }
+
+    fn write_pending(&mut self, pending: PendingFrame, until: u64) -> Result<(), String> {
+        let delay = (until as f64 * self.frame_delay).round()
+            - (pending.index as f64 * self.frame_delay).round();
+        let frame = gif::Frame {
+            width: pending.width,
+            height: pending.height,
+            delay: delay.max(1.0) as u16,
+            buffer: Cow::Owned(pending.indices),
+            ..gif::Frame::default()
+        };
+        self.encoder(pending.width, pending.height)?
+            .write_frame(&frame)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+        self.kept += 1;
+        Ok(())
+    }
+
+    fn update_skip(&mut self, index: u64) {
+        let (Some(max_bytes), Some(encoder)) = (self.max_bytes, self.encoder.as_ref()) else {
+            return;
+        };
+        let written = encoder.get_ref().written;
+        let average = written as f64 / self.kept as f64;
+        let remaining = self.window.end.saturating_sub(index) as f64;
+        let budget = max_bytes.saturating_sub(written) as f64;
+        self.skip = if budget <= average {
+            u64::MAX
+        } else if self.window.end == u64::MAX {
+            1
+        } else {
+            ((remaining * average / budget).ceil() as u64).max(1)
+        };
+    }
+}
+
+impl FrameSink for GifWriter {
+    fn write_frame(&mut self, frame: &Frame) -> Result<(), String> {
+        let index = self.index;
+        self.index += 1;
+        if !self.window.contains(&index) {
+            return Ok(());
+        }
+        if let Some(pending) = &self.pending {
+            if index - pending.index < self.skip {
+                return Ok(());
+            }
+        }
+
+        let (width, height) = (
+            u16::try_from(frame.width()).map_err(|e| e.to_string())?,
+            u16::try_from(frame.height()).map_err(|e| e.to_string())?,
+        );
+        let indices = self.palette.quantize(frame);
+        if let Some(pending) = self.pending.take() {
+            self.write_pending(pending, index)?;
+            self.update_skip(index);
+        }
+        self.pending = Some(PendingFrame {
+            index,
+            width,
+            height,
+            indices,
+        });
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        if let Some(pending) = self.pending.take() {
+            let until = self.index.min(self.window.end).max(pending.index + 1);
+            self.write_pending(pending, until)?;
+        }
+        if let Some(encoder) = self.encoder.take() {
+            encoder
+                .into_inner()
+                .and_then(|mut writer| writer.flush())
+                .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+/// Buckets items by the cell their centre falls in, so collision checks only
+/// look at neighbouring cells instead of every object.
+pub struct SpatialGrid<T> {
+    cell_width: f64,
+    cell_height: f64,
+    columns: i64,
+    rows: i64,
+    /// The world size when it is a torus.
+    wrap: Option<(f64, f64)>,
+    cells: HashMap<(i64, i64), Vec<T>>,
+}
+
+impl<T> SpatialGrid<T> {
+    /// `cell_size` must be at least the largest object so that touching
+    /// objects are never more than one cell apart. Cells are stretched so a
+    /// whole number of them fills the world, which keeps the last column
+    /// next to the first one on a torus.
+    pub fn new(
+        cell_size: f64,
+        width: f64,
+        height: f64,
+        wrap: Option<(f64, f64)>,
+    ) -> SpatialGrid<T> {
+        assert!(cell_size > 0.0, "cell size must be greater than 0");
+        let columns = (width / cell_size).floor().max(1.0);
+        let rows = (height / cell_size).floor().max(1.0);
+        SpatialGrid {
+            cell_width: (width / columns).max(cell_size),
+            cell_height: (height / rows).max(cell_size),
+            columns: columns as i64,
+            rows: rows as i64,
+            wrap,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// The smaller cell side; anything within this distance of a point is in
+    /// the block `nearby` returns.
+    #[inline]
+    pub fn cell_size(&self) -> f64 {
+        self.cell_width.min(self.cell_height)
+    }
+
+    pub fn insert(&mut self, center: (f64, f64), item: T) {
+        let cell = self.cell(center);
+        self.cells.entry(cell).or_default().push(item);
+    }
+
+    /// Items in the 3x3 block of cells around `center`; on a torus the block
+    /// wraps around the edges.
+    pub fn nearby(&self, center: (f64, f64)) -> impl Iterator<Item = &T> {
+        let (column, row) = self.cell(center);
+        let mut cells = Vec::with_capacity(9);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let cell = if self.wrap.is_some() {
+                    (
+                        (column + dx).rem_euclid(self.columns),
+                        (row + dy).rem_euclid(self.rows),
+                    )
+                } else {
+                    (column + dx, row + dy)
+                };
+                // 列数が少ないと同じセルを二度見てしまう
+                if !cells.contains(&cell) {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+            .into_iter()
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+
+    fn cell(&self, (x, y): (f64, f64)) -> (i64, i64) {
+        // 右端をまたぐオブジェクトの中心は幅を超えるので折り返す
+        let (x, y) = match self.wrap {
+            Some((width, height)) => (x.rem_euclid(width), y.rem_euclid(height)),
+            None => (x, y),
+        };
+        (
+            ((x / self.cell_width).floor() as i64).min(self.columns - 1),
+            ((y / self.cell_height).floor() as i64).min(self.rows - 1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(grid: &SpatialGrid<u32>, center: (f64, f64), item: u32) -> bool {
+        grid.nearby(center).any(|other| *other == item)
+    }
+
+    #[test]
+    fn finds_neighbours_across_the_wrapped_edge() {
+        // 右端に乗った 25px の物体の中心は x = 812 になる
+        for (width, height) in [(800.0, 600.0), (810.0, 615.0)] {
+            let mut grid = SpatialGrid::new(25.0, width, height, Some((width, height)));
+            grid.insert((width + 12.0, 300.0), 1);
+            grid.insert((300.0, height + 12.0), 2);
+            assert!(found(&grid, (5.0, 300.0), 1), "{}x{}", width, height);
+            assert!(found(&grid, (300.0, 5.0), 2), "{}x{}", width, height);
+
+            let mut grid = SpatialGrid::new(25.0, width, height, Some((width, height)));
+            grid.insert((5.0, 300.0), 1);
+            grid.insert((5.0, 5.0), 2);
+            assert!(found(&grid, (width + 12.0, 300.0), 1));
+            assert!(found(&grid, (width - 10.0, height - 10.0), 2));
+        }
+    }
+
+    #[test]
+    fn does_not_wrap_between_walls() {
+        let mut grid = SpatialGrid::new(25.0, 800.0, 600.0, None);
+        grid.insert((790.0, 300.0), 1);
+        assert!(!found(&grid, (5.0, 300.0), 1));
+        assert!(found(&grid, (770.0, 300.0), 1));
+    }
+
+    #[test]
+    fn cells_cover_the_world_exactly() {
+        let grid = SpatialGrid::<u32>::new(25.0, 810.0, 600.0, Some((810.0, 600.0)));
+        assert_eq!(grid.columns, 32);
+        assert_eq!(grid.cell((809.9, 0.0)), (31, 0));
+        assert_eq!(grid.cell((810.0, 0.0)), (0, 0));
+    }
+}
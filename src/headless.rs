@@ -0,0 +1,56 @@
+use log::info;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+
+use crate::args::Args;
+use crate::assets::Assets;
+use crate::camera::Camera;
+use crate::error::StartupError;
+use crate::minimap;
+use crate::screen::Screen;
+use crate::species::SpeciesRegistry;
+
+/// Runs a match without a window, rendering into an offscreen surface.
+pub fn run(args: &Args) -> Result<(), StartupError> {
+    let (width, height) = args.resolution();
+    let surface =
+        Surface::new(width, height, PixelFormatEnum::ARGB8888).map_err(StartupError::Sdl)?;
+    let mut canvas = surface.into_canvas().map_err(StartupError::Sdl)?;
+
+    let ttf_context = sdl2::ttf::init().map_err(|e| StartupError::Sdl(e.to_string()))?;
+    let (source, theme) = crate::theme(args)?;
+    let font = source.load_font(&ttf_context, theme.font(), theme.font_size())?;
+
+    let texture_creator = canvas.texture_creator();
+    let mut assets = Assets::new(font, theme);
+    let species = SpeciesRegistry::classic(&mut assets, &texture_creator, &source)?;
+    let (world_width, world_height) = args.world();
+    let mut screen = Screen::new(world_width, world_height, species);
+    crate::populate(&mut screen, args)?;
+
+    let mut recorder = crate::recorder(args, &source, assets.theme(), screen.species())?;
+    if recorder.is_empty() {
+        return Err(StartupError::NoOutput);
+    }
+
+    // 解像度の縦横比が世界と違っても歪ませず、余白を残して中央に収める
+    let camera = Camera::fit(screen.width(), screen.height(), width, height);
+    let mut written = 0;
+    while written < args.frames() {
+        screen.next_frame();
+        if !recorder.is_due(screen.tick()) {
+            continue;
+        }
+        canvas.set_draw_color(assets.theme().background());
+        canvas.clear();
+        screen.draw(&mut canvas, &texture_creator, &assets, &camera);
+        if args.minimap() {
+            minimap::draw(&mut canvas, &screen, &camera, assets.theme());
+        }
+        recorder.capture(&canvas).unwrap();
+        written += 1;
+    }
+    recorder.finish().unwrap();
+    info!("Rendered {} frames", written);
+    Ok(())
+}
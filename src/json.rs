@@ -0,0 +1,30 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+/// Reads a JSON file, prefixing any error with the path.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_name_the_file() {
+        let path = std::env::temp_dir().join("rps-read-json-test.json");
+        std::fs::write(&path, "[1, 2,").unwrap();
+        let error = read_json::<Vec<u32>>(&path).unwrap_err();
+        assert!(error.starts_with(&format!("{}: ", path.display())));
+        std::fs::write(&path, "[1, 2, 3]").unwrap();
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), vec![1, 2, 3]);
+        std::fs::remove_file(&path).unwrap();
+
+        let missing = read_json::<Vec<u32>>(&path).unwrap_err();
+        assert!(missing.starts_with(&format!("{}: ", path.display())));
+    }
+}
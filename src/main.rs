@@ -1,120 +1,123 @@
-use std::env;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
+use std::{env, process};
 
+use log::{error, info};
 use sdl2::event::Event;
-use sdl2::image::LoadTexture;
 use sdl2::keyboard::Keycode;
-use sdl2::pixels::Color;
+use sdl2::mouse::MouseButton;
 
-use crate::paper::Paper;
-use crate::position::Position;
-use crate::rock::Rock;
-use crate::scissors::Scissors;
+use crate::arena::Arena;
+use crate::args::Args;
+use crate::assets::{AssetSource, Assets};
+use crate::boundary::Boundary;
+use crate::camera::{Camera, Follow};
+use crate::error::StartupError;
+use crate::forces::Forces;
+use crate::frame::Recorder;
+use crate::gif_writer::{GifWriter, Palette};
+use crate::obstacle::Obstacle;
+use crate::png_sequence::PngSequence;
 use crate::screen::Screen;
-use crate::screen_object::ScreenObject;
-use crate::velocity::Velocity;
+use crate::snapshot::Snapshot;
+use crate::spawn::Layout;
+use crate::species::{ConversionPolicy, SpeciesRegistry, TraitOverrides};
+use crate::steering::ChaseFlee;
+use crate::theme::Theme;
+use crate::y4m::Y4mWriter;
 
+mod animation;
+mod arena;
+mod args;
+mod assets;
+mod behavior;
+mod boundary;
+mod camera;
+mod error;
+mod forces;
+mod frame;
+mod gif_writer;
+mod grid;
+mod headless;
+mod json;
+mod minimap;
 mod object;
-mod paper;
+mod obstacle;
+mod particle;
+mod png_sequence;
 mod position;
-mod rock;
-mod scissors;
 mod screen;
 mod screen_object;
 mod se;
+mod shape;
+mod snapshot;
+mod spawn;
+mod species;
+mod steering;
+mod theme;
+mod trail;
 mod velocity;
-mod sample;
+mod y4m;
 
 pub const SCREEN_WIDTH: u32 = 800;
 pub const SCREEN_HEIGHT: u32 = 800;
+pub const TICKS_PER_SECOND: u32 = 150;
+const PAN_STEP: f64 = 40.0;
+const ZOOM_STEP: f64 = 1.1;
 
 pub fn main() {
-    let sdl_context = sdl2::init().unwrap();
-    let video_subsystem = sdl_context.video().unwrap();
+    let args = Args::parse();
+
+    // init logger
+    env::set_var("RUST_LOG", "debug");
+    env_logger::init();
+
+    let result = if args.headless() {
+        headless::run(&args)
+    } else {
+        run(&args)
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), StartupError> {
+    let sdl_context = sdl2::init().map_err(StartupError::Sdl)?;
+    let video_subsystem = sdl_context.video().map_err(StartupError::Sdl)?;
     let window = video_subsystem
         .window("Rock Scissors Paper Battle", SCREEN_WIDTH, SCREEN_HEIGHT)
         .position_centered()
         .build()
-        .unwrap();
-    let mut canvas = window.into_canvas().build().unwrap();
-    let mut event_pump = sdl_context.event_pump().unwrap();
+        .map_err(|e| StartupError::Sdl(e.to_string()))?;
+    let mut canvas = window
+        .into_canvas()
+        .build()
+        .map_err(|e| StartupError::Sdl(e.to_string()))?;
+    let mut event_pump = sdl_context.event_pump().map_err(StartupError::Sdl)?;
 
-    let ttf_context = sdl2::ttf::init().map_err(|e| e.to_string()).unwrap();
-    let font = ttf_context
-        .load_font(Path::new("assets/SourceCodePro-Bold.ttf"), 40)
-        .unwrap();
+    let ttf_context = sdl2::ttf::init().map_err(|e| StartupError::Sdl(e.to_string()))?;
+    let (source, theme) = theme(args)?;
+    let font = source.load_font(&ttf_context, theme.font(), theme.font_size())?;
 
     let texture_creator = canvas.texture_creator();
 
-    // init logger
-    env::set_var("RUST_LOG", "debug");
-    env_logger::init();
-
-    let rock_texture = texture_creator.load_texture("assets/rock.png").unwrap();
-    let paper_texture = texture_creator.load_texture("assets/paper.png").unwrap();
-    let scissors_texture = texture_creator.load_texture("assets/scissors.png").unwrap();
-    let mut screen = Screen::new(SCREEN_WIDTH, SCREEN_HEIGHT, font);
-
-    let objects_count = 60;
-    for i in 0..objects_count {
-        let size = 1500 / objects_count;
-        let (width, height, mass, velocity) = (size, size, 1f64, Velocity::random(1.1, 1.1));
-
-        let a = vec![1, 2, 3].iter(;
-        let object = match i % 3 {
-            0 => ScreenObject::Paper(Paper::new(
-                Position::random(
-                    0.0..(SCREEN_WIDTH as f64 / 5.0),
-                    0.0..(SCREEN_HEIGHT as f64 / 5.0),
-                    (SCREEN_WIDTH - size) as f64,
-                    (SCREEN_HEIGHT - size) as f64,
-                ),
-                width,
-                height,
-                mass,
-                velocity,
-                &paper_texture,
-                &scissors_texture,
-                &rock_texture,
-            )),
-            1 => ScreenObject::Rock(Rock::new(
-                Position::random(
-                    SCREEN_WIDTH as f64 - (SCREEN_WIDTH as f64 / 5.0)..SCREEN_WIDTH as f64,
-                    0.0..(SCREEN_HEIGHT as f64 / 5.0),
-                    (SCREEN_HEIGHT - size) as f64,
-                    (SCREEN_HEIGHT - size) as f64,
-                ),
-                width,
-                height,
-                mass,
-                velocity,
-                &rock_texture,
-                &paper_texture,
-                &scissors_texture,
-            )),
-            2 => ScreenObject::Scissors(Scissors::new(
-                Position::random(
-                    (SCREEN_WIDTH as f64 / 5.0)..SCREEN_WIDTH as f64,
-                    SCREEN_HEIGHT as f64 - (SCREEN_HEIGHT as f64 / 5.0)..SCREEN_HEIGHT as f64,
-                    (SCREEN_WIDTH - size) as f64,
-                    (SCREEN_HEIGHT - size) as f64,
-                ),
-                width,
-                height,
-                mass,
-                velocity,
-                &scissors_texture,
-                &rock_texture,
-                &paper_texture,
-            )),
-            _ => unreachable!(),
-        };
-
-        screen.add_object(object);
-    }
+    let mut assets = Assets::new(font, theme);
+    let species = SpeciesRegistry::classic(&mut assets, &texture_creator, &source)?;
+    let (world_width, world_height) = args.world();
+    let mut screen = Screen::new(world_width, world_height, species);
+    populate(&mut screen, args)?;
 
+    let mut recorder = recorder(args, &source, assets.theme(), screen.species())?;
     let mut running = false;
+    let mut camera = Camera::new(screen.width(), screen.height(), SCREEN_WIDTH, SCREEN_HEIGHT);
+    let mut minimap =
+        args.minimap() || screen.width() > SCREEN_WIDTH || screen.height() > SCREEN_HEIGHT;
+    // ドラッグ中の障害物の始点（ワールド座標）
+    let mut drag_start = None;
+    let mut mouse = (SCREEN_WIDTH as f64 / 2.0, SCREEN_HEIGHT as f64 / 2.0);
 
     'running: loop {
         for event in event_pump.poll_iter() {
@@ -123,25 +126,318 @@ pub fn main() {
                 | Event::KeyDown {
                     keycode: Some(Keycode::Escape),
                     ..
-                } => break 'running,
+                } => {
+                    recorder.finish().unwrap();
+                    break 'running;
+                }
                 Event::KeyDown {
                     keycode: Some(Keycode::Return),
                     ..
                 } => {
                     running = true;
                 }
+                Event::KeyDown {
+                    keycode: Some(Keycode::T),
+                    ..
+                } => {
+                    screen.set_trails_enabled(!screen.trails_enabled());
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::S),
+                    ..
+                } => {
+                    let path = PathBuf::from(format!("snapshot-{}.json", screen.tick()));
+                    match screen.snapshot().save(&path) {
+                        Ok(()) => info!("Saved snapshot to {}", path.display()),
+                        Err(e) => error!("Failed to save snapshot: {}", e),
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } if pan_direction(keycode).is_some() => {
+                    let (dx, dy) = pan_direction(keycode).unwrap();
+                    camera.pan(dx * PAN_STEP, dy * PAN_STEP);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Equals | Keycode::KpPlus),
+                    ..
+                } => {
+                    camera.zoom_at(ZOOM_STEP, mouse);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Minus | Keycode::KpMinus),
+                    ..
+                } => {
+                    camera.zoom_at(1.0 / ZOOM_STEP, mouse);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::M),
+                    ..
+                } => {
+                    minimap = !minimap;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::F),
+                    ..
+                } => {
+                    camera.set_follow(Follow::Survivors);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Num0),
+                    ..
+                } => {
+                    camera =
+                        Camera::new(screen.width(), screen.height(), SCREEN_WIDTH, SCREEN_HEIGHT);
+                }
+                Event::MouseWheel { y, .. } => {
+                    camera.zoom_at(ZOOM_STEP.powi(y), mouse);
+                }
+                Event::MouseMotion {
+                    mousestate,
+                    x,
+                    y,
+                    xrel,
+                    yrel,
+                    ..
+                } => {
+                    mouse = (x as f64, y as f64);
+                    if mousestate.middle() {
+                        camera.pan(-xrel as f64, -yrel as f64);
+                    }
+                }
+                Event::MouseButtonDown {
+                    mouse_btn, x, y, ..
+                } => {
+                    let (x, y) = camera.to_world((x as f64, y as f64));
+                    drag_start = Some((mouse_btn, x, y));
+                }
+                Event::MouseButtonUp { x, y, .. } => {
+                    let end = camera.to_world((x as f64, y as f64));
+                    if let Some(start) = drag_start.take() {
+                        match dragged_obstacle(start, end) {
+                            Some(obstacle) => screen.add_obstacle(obstacle),
+                            // ドラッグせずにクリックしたらそのオブジェクトを追う
+                            None if start.0 == MouseButton::Left => {
+                                if let Some(id) = screen.object_at(end) {
+                                    camera.set_follow(Follow::Object(id));
+                                }
+                            }
+                            None => {}
+                        }
+                    }
+                }
                 _ => {}
             }
         }
         if !running {
             continue;
         }
-        canvas.set_draw_color(Color::RGB(0, 0, 0));
+        canvas.set_draw_color(assets.theme().background());
         canvas.clear();
 
-        screen.draw(&mut canvas);
+        screen.next_frame();
+        camera.update(&screen);
+        screen.draw(&mut canvas, &texture_creator, &assets, &camera);
+        if minimap {
+            minimap::draw(&mut canvas, &screen, &camera, assets.theme());
+        }
+        if recorder.is_due(screen.tick()) {
+            recorder.capture(&canvas).unwrap();
+        }
 
         canvas.present();
-        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 150));
+        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / TICKS_PER_SECOND));
+    }
+    Ok(())
+}
+
+fn pan_direction(keycode: Keycode) -> Option<(f64, f64)> {
+    match keycode {
+        Keycode::Left => Some((-1.0, 0.0)),
+        Keycode::Right => Some((1.0, 0.0)),
+        Keycode::Up => Some((0.0, -1.0)),
+        Keycode::Down => Some((0.0, 1.0)),
+        _ => None,
+    }
+}
+
+/// Left-drag draws a rectangle, right-drag a circle centred on the press.
+fn dragged_obstacle(
+    (button, start_x, start_y): (MouseButton, f64, f64),
+    (end_x, end_y): (f64, f64),
+) -> Option<Obstacle> {
+    let obstacle = match button {
+        MouseButton::Left => Obstacle::Rect {
+            x: start_x.min(end_x),
+            y: start_y.min(end_y),
+            width: (end_x - start_x).abs(),
+            height: (end_y - start_y).abs(),
+        },
+        MouseButton::Right => Obstacle::Circle {
+            x: start_x,
+            y: start_y,
+            radius: (end_x - start_x).hypot(end_y - start_y),
+        },
+        _ => return None,
+    };
+    match obstacle {
+        Obstacle::Rect { width, height, .. } if width < 2.0 || height < 2.0 => None,
+        Obstacle::Circle { radius, .. } if radius < 2.0 => None,
+        _ => Some(obstacle),
+    }
+}
+
+fn theme(args: &Args) -> Result<(AssetSource, Theme), StartupError> {
+    let (source, mut theme) = match args.theme() {
+        Some(dir) => {
+            let theme = Theme::load(dir).map_err(StartupError::Theme)?;
+            (AssetSource::Dir(dir.clone()), theme)
+        }
+        None => (AssetSource::new(args.assets_dir()), Theme::default()),
+    };
+    if args.shapes() {
+        theme.set_shapes(true);
+    }
+    Ok((source, theme))
+}
+
+fn populate(screen: &mut Screen, args: &Args) -> Result<(), StartupError> {
+    let arena = match args.arena() {
+        None | Some("rect") => Arena::Rect,
+        Some("circle") => Arena::circle(screen.width(), screen.height()),
+        Some("hexagon") => Arena::regular(6, screen.width(), screen.height()),
+        Some(path) => Arena::load(Path::new(path)).map_err(StartupError::Arena)?,
+    };
+    if arena != Arena::Rect && args.wrap() {
+        return Err(StartupError::Arena(
+            "--wrap only works with the rectangular arena".to_string(),
+        ));
+    }
+    screen.set_arena(arena);
+    if let Some(path) = args.traits() {
+        let traits = TraitOverrides::load(path).map_err(StartupError::Traits)?;
+        for (species, traits) in traits.iter() {
+            let id = screen
+                .species()
+                .find(species)
+                .ok_or_else(|| StartupError::Traits(format!("unknown species: {}", species)))?;
+            screen.species_mut().apply_traits(id, traits);
+        }
+    }
+    if args.adopt_traits() {
+        screen.set_conversion_policy(ConversionPolicy::Adopt);
+    }
+    if let Some(path) = args.snapshot() {
+        let snapshot = Snapshot::load(path).map_err(StartupError::Snapshot)?;
+        screen
+            .restore(&snapshot)
+            .map_err(|e| StartupError::Snapshot(format!("{}: {}", path.display(), e)))?;
+    }
+    if let Some(path) = args.obstacles() {
+        let obstacles = Obstacle::load(path).map_err(StartupError::Obstacles)?;
+        screen.set_obstacles(obstacles);
+    }
+    if args.snapshot().is_none() {
+        let layout = match Layout::parse(args.spawn()) {
+            Some(layout) => layout,
+            None => Layout::load(Path::new(args.spawn())).map_err(StartupError::Spawn)?,
+        };
+        let mut speeds = HashMap::new();
+        if let Some(speed) = args.speed() {
+            for (id, _) in screen.species().iter() {
+                speeds.insert(id, speed);
+            }
+        }
+        for (species, speed) in args.species_speeds() {
+            let id = screen
+                .species()
+                .find(species)
+                .ok_or_else(|| StartupError::Speed(format!("unknown species: {}", species)))?;
+            speeds.insert(id, *speed);
+        }
+        spawn::spawn(screen, &layout, args.objects(), &speeds).map_err(StartupError::Spawn)?;
+    }
+    screen.set_trails_enabled(args.trails());
+    if args.wrap() {
+        screen.set_boundary(Boundary::Wrap);
+    }
+    let forces =
+        Forces::new(args.drag(), args.gravity(), args.jitter()).map_err(StartupError::Forces)?;
+    screen.set_forces(forces);
+    if args.steering() {
+        let ids = screen
+            .species()
+            .iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        for id in ids {
+            let behavior = ChaseFlee::new(args.perception(), args.max_acceleration())
+                .map_err(StartupError::Steering)?;
+            screen.set_behavior(id, Box::new(behavior));
+        }
+    }
+    for (species, name) in args.behaviors() {
+        let id = screen
+            .species()
+            .find(species)
+            .ok_or_else(|| StartupError::Behavior(format!("unknown species: {}", species)))?;
+        let behavior = steering::by_name(name, args.perception(), args.max_acceleration())
+            .map_err(StartupError::Steering)?
+            .ok_or_else(|| StartupError::Behavior(format!("unknown behavior: {}", name)))?;
+        screen.set_behavior(id, behavior);
+    }
+    Ok(())
+}
+
+fn recorder(
+    args: &Args,
+    source: &AssetSource,
+    theme: &Theme,
+    species: &SpeciesRegistry,
+) -> Result<Recorder, StartupError> {
+    let mut recorder = Recorder::new(args.fps() as u64, TICKS_PER_SECOND as u64);
+    if let Some(dir) = args.frames_dir() {
+        let sequence = PngSequence::new(dir.clone()).map_err(StartupError::Output)?;
+        recorder.add_sink(Box::new(sequence));
+    }
+    if let Some(path) = args.record() {
+        let writer = Y4mWriter::new(path.clone(), (args.fps(), 1)).map_err(StartupError::Output)?;
+        recorder.add_sink(Box::new(writer));
+    }
+    if let Some(path) = args.gif() {
+        let frames_per_second = args.fps() as f64;
+        let from = (args.gif_from() * frames_per_second) as u64;
+        let to = match args.gif_to() {
+            Some(to) => (to * frames_per_second) as u64,
+            // ウィンドウ表示では --frames に意味がないので閉じるまで録画する
+            None if args.headless() => args.frames(),
+            None => u64::MAX,
+        };
+        let mut sprites = Vec::new();
+        let background = theme.background();
+        let text_color = theme.text_color();
+        let mut reserved = vec![
+            [background.r, background.g, background.b],
+            [text_color.r, text_color.g, text_color.b],
+        ];
+        for (_, species) in species.iter() {
+            let color = species.color();
+            if species.texture().is_some() {
+                sprites.push(source.load_surface(species.sprite())?);
+            }
+            reserved.push([color.r, color.g, color.b]);
+        }
+        let palette = Palette::from_sprites(&sprites, &reserved).map_err(StartupError::Output)?;
+        let writer = GifWriter::new(
+            path.clone(),
+            palette,
+            100.0 / frames_per_second,
+            from..to,
+            args.gif_max_bytes(),
+        )
+        .map_err(StartupError::Output)?;
+        recorder.add_sink(Box::new(writer));
     }
+    Ok(recorder)
 }
@@ -0,0 +1,73 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas, RenderTarget};
+
+use crate::camera::Camera;
+use crate::screen::Screen;
+use crate::theme::Theme;
+
+/// Length of the minimap's longer side, in view pixels.
+const MINIMAP_SIZE: f64 = 160.0;
+const MARGIN: i32 = 10;
+const DOT_SIZE: u32 = 2;
+
+/// Draws the whole world in the bottom-right corner of the view, with every
+/// object as a dot and the camera's viewport as a rectangle.
+pub fn draw<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    screen: &Screen,
+    camera: &Camera,
+    theme: &Theme,
+) {
+    let (world_width, world_height) = (screen.width() as f64, screen.height() as f64);
+    let scale = MINIMAP_SIZE / world_width.max(world_height);
+    let (view_width, view_height) = camera.view_size();
+    let (width, height) = (
+        (world_width * scale).round() as u32,
+        (world_height * scale).round() as u32,
+    );
+    let frame = Rect::new(
+        view_width as i32 - width as i32 - MARGIN,
+        view_height as i32 - height as i32 - MARGIN,
+        width,
+        height,
+    );
+    let to_minimap = |(x, y): (f64, f64)| {
+        (
+            frame.x() + (x * scale) as i32,
+            frame.y() + (y * scale) as i32,
+        )
+    };
+
+    let background = theme.background();
+    canvas.set_blend_mode(BlendMode::Blend);
+    canvas.set_draw_color(Color::RGBA(background.r, background.g, background.b, 200));
+    canvas.fill_rect(frame).expect("failed to draw minimap");
+    canvas.set_blend_mode(BlendMode::None);
+
+    canvas.set_clip_rect(frame);
+    for (species, center) in screen.centers() {
+        let (x, y) = to_minimap(center);
+        canvas.set_draw_color(screen.species().get(species).color());
+        canvas
+            .fill_rect(Rect::new(x, y, DOT_SIZE, DOT_SIZE))
+            .expect("failed to draw minimap");
+    }
+    let ((left, top), (right, bottom)) = (
+        to_minimap(camera.to_world((0.0, 0.0))),
+        to_minimap(camera.to_world((view_width, view_height))),
+    );
+    canvas.set_draw_color(theme.text_color());
+    canvas
+        .draw_rect(Rect::new(
+            left,
+            top,
+            (right - left).max(1) as u32,
+            (bottom - top).max(1) as u32,
+        ))
+        .expect("failed to draw minimap");
+    canvas.set_clip_rect(None);
+
+    canvas.set_draw_color(theme.obstacle_color());
+    canvas.draw_rect(frame).expect("failed to draw minimap");
+}
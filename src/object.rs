@@ -1,17 +1,29 @@
-use sdl2::rect::Rect;
-use sdl2::render::{Canvas, Texture};
-use sdl2::video::Window;
+use sdl2::render::{Canvas, RenderTarget, Texture};
+use serde::{Deserialize, Serialize};
 
+use crate::camera::Camera;
 use crate::position::Position;
 use crate::velocity::Velocity;
 
-#[derive(Debug, Clone, Copy)]
+/// How strongly an off-centre hit turns into spin, in degrees per tick.
+const SPIN_FACTOR: f64 = 4.0;
+const ANGULAR_DAMPING: f64 = 0.995;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Object {
     position: Position,
     width: u32,
     height: u32,
     mass: f64,
     velocity: Velocity,
+    /// 度数法、時計回り
+    #[serde(default)]
+    angle: f64,
+    #[serde(default)]
+    angular_velocity: f64,
+    /// Top speed under steering; `None` means the species speed.
+    #[serde(default)]
+    speed_limit: Option<f64>,
 }
 
 impl Object {
@@ -29,6 +41,9 @@ impl Object {
             height,
             mass,
             velocity,
+            angle: 0.0,
+            angular_velocity: 0.0,
+            speed_limit: None,
         }
     }
 
@@ -47,6 +62,23 @@ impl Object {
         self.height
     }
 
+    #[inline]
+    pub fn mass(&self) -> f64 {
+        self.mass
+    }
+
+    #[inline]
+    pub fn angle(&self) -> f64 {
+        self.angle
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (
+            self.position.x() + self.width as f64 / 2.0,
+            self.position.y() + self.height as f64 / 2.0,
+        )
+    }
+
     pub fn range_x(&self) -> (f64, f64) {
         (self.position.x(), self.position.x() + self.width as f64)
     }
@@ -102,31 +134,162 @@ impl Object {
             + 2.0 * self.mass * self.velocity.y())
             / (self.mass + other.mass);
 
+        // 中心からずれた衝突ほど強く回転させる
+        let ((self_x, self_y), (other_x, other_y)) = (self.center(), other.center());
+        let (offset_x, offset_y) = (other_x - self_x, other_y - self_y);
+        let (relative_x, relative_y) = (
+            other.velocity.x() - self.velocity.x(),
+            other.velocity.y() - self.velocity.y(),
+        );
+        let distance = offset_x.hypot(offset_y);
+        if distance > f64::EPSILON {
+            let spin = (offset_x * relative_y - offset_y * relative_x) / distance * SPIN_FACTOR;
+            self.angular_velocity += spin * other.mass / (self.mass + other.mass);
+            other.angular_velocity += spin * self.mass / (self.mass + other.mass);
+        }
+
         self.velocity = Velocity::new(self_velocity_x, self_velocity_y);
         other.velocity = Velocity::new(other_velocity_x, other_velocity_y);
     }
 
-    pub fn next_frame(&mut self) {
-        self.position
-            .move_distance(self.velocity.x(), self.velocity.y());
+    /// `wrap` is the arena size when the arena is a torus.
+    pub fn next_frame(&mut self, wrap: Option<(f64, f64)>) {
+        self.angle = (self.angle + self.angular_velocity).rem_euclid(360.0);
+        self.angular_velocity *= ANGULAR_DAMPING;
+        match wrap {
+            Some((width, height)) => {
+                self.position
+                    .move_wrapped(self.velocity.x(), self.velocity.y(), width, height)
+            }
+            None => self
+                .position
+                .move_distance(self.velocity.x(), self.velocity.y()),
+        }
+    }
+
+    /// Changes size and mass, keeping the centre where it is.
+    pub fn reshape(&mut self, width: u32, height: u32, mass: f64) {
+        assert!(mass > 0f64, "mass must be greater than 0");
+        self.position.grow(
+            width as f64 - self.width as f64,
+            height as f64 - self.height as f64,
+        );
+        self.width = width;
+        self.height = height;
+        self.mass = mass;
+    }
+
+    pub fn translate(&mut self, x: f64, y: f64) {
+        self.position = self.position.offset(x, y);
+    }
+
+    #[inline]
+    pub fn speed_limit(&self) -> Option<f64> {
+        self.speed_limit
+    }
+
+    pub fn set_speed_limit(&mut self, speed: f64) {
+        self.speed_limit = Some(speed);
     }
 
     pub fn velocity_mut(&mut self) -> &mut Velocity {
         &mut self.velocity
     }
 
-    pub fn draw(&self, canvas: &mut Canvas<Window>, texture: &Texture) {
+    /// `(x, y, width, height)` in world coordinates, scaled about the centre.
+    pub fn bounds(&self, scale: f64) -> (f64, f64, f64, f64) {
+        let (center_x, center_y) = self.center();
+        let (width, height) = (self.width as f64 * scale, self.height as f64 * scale);
+        (
+            center_x - width / 2.0,
+            center_y - height / 2.0,
+            width,
+            height,
+        )
+    }
+
+    pub fn draw<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        camera: &Camera,
+        texture: &Texture,
+        scale: f64,
+    ) {
         canvas
-            .copy(
+            .copy_ex(
                 texture,
                 None,
-                Some(Rect::new(
-                    self.position.x() as i32,
-                    self.position.y() as i32,
-                    self.width,
-                    self.height,
-                )),
+                Some(camera.to_screen_rect(self.bounds(scale))),
+                self.angle,
+                None,
+                false,
+                false,
             )
             .expect("failed to copy texture");
     }
 }
+
+/// A still, unit-mass square in a 1000x1000 world, for tests that only care
+/// where an object is.
+#[cfg(test)]
+pub(crate) fn square(x: f64, y: f64, size: u32) -> Object {
+    Object::new(
+        Position::new(x, y, 1000.0, 1000.0),
+        size,
+        size,
+        1.0,
+        Velocity::new(0.0, 0.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(x: f64, y: f64, mass: f64, vx: f64) -> Object {
+        Object::new(
+            Position::new(x, y, 1000.0, 1000.0),
+            10,
+            10,
+            mass,
+            Velocity::new(vx, 0.0),
+        )
+    }
+
+    #[test]
+    fn head_on_hits_add_no_spin() {
+        let (mut a, mut b) = (body(100.0, 100.0, 1.0, 1.0), body(110.0, 100.0, 2.0, -1.0));
+        a.collide(&mut b);
+        assert_eq!((a.angular_velocity, b.angular_velocity), (0.0, 0.0));
+    }
+
+    #[test]
+    fn off_centre_hits_spin_both_bodies_the_same_way_weighted_by_mass() {
+        let (mut light, mut heavy) = (body(100.0, 100.0, 1.0, 1.0), body(105.0, 108.0, 3.0, 0.0));
+        light.collide(&mut heavy);
+        assert!(light.angular_velocity > 0.0);
+        assert!(heavy.angular_velocity > 0.0);
+        // 軽い方が重い方の質量ぶん大きく回る
+        assert!((light.angular_velocity - 3.0 * heavy.angular_velocity).abs() < 1e-9);
+
+        // 反対側をかすめると逆向きに回る
+        let (mut light, mut heavy) = (body(100.0, 100.0, 1.0, 1.0), body(105.0, 92.0, 3.0, 0.0));
+        light.collide(&mut heavy);
+        assert!(light.angular_velocity < 0.0 && heavy.angular_velocity < 0.0);
+    }
+
+    #[test]
+    fn angle_wraps_modulo_360() {
+        let mut object = body(100.0, 100.0, 1.0, 0.0);
+        object.angle = 350.0;
+        object.angular_velocity = 30.0;
+        object.next_frame(None);
+        assert!((object.angle - 20.0).abs() < 1e-9);
+        assert!(object.angular_velocity < 30.0);
+
+        object.angle = 10.0;
+        object.angular_velocity = -30.0;
+        object.next_frame(None);
+        assert!((object.angle - 340.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,136 @@
+use std::path::Path;
+
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::camera::Camera;
+use crate::json::read_json;
+use crate::object::Object;
+use crate::shape::Shape;
+
+/// Immovable terrain inside the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "lowercase")]
+pub enum Obstacle {
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    Circle {
+        x: f64,
+        y: f64,
+        radius: f64,
+    },
+}
+
+impl Obstacle {
+    /// Reads a JSON array of obstacles.
+    pub fn load(path: &Path) -> Result<Vec<Obstacle>, String> {
+        read_json(path)
+    }
+
+    /// Returns the unit normal pointing out of the obstacle if `object` overlaps it.
+    pub fn normal(&self, object: &Object) -> Option<(f64, f64)> {
+        let (left, right) = object.range_x();
+        let (top, bottom) = object.range_y();
+        match *self {
+            Obstacle::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                if right < x || left > x + width || bottom < y || top > y + height {
+                    return None;
+                }
+                // 一番浅くめり込んでいる辺から押し返す
+                [
+                    (right - x, (-1.0, 0.0)),
+                    (x + width - left, (1.0, 0.0)),
+                    (bottom - y, (0.0, -1.0)),
+                    (y + height - top, (0.0, 1.0)),
+                ]
+                .into_iter()
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, normal)| normal)
+            }
+            Obstacle::Circle { x, y, radius } => {
+                let (dx, dy) = (x.clamp(left, right) - x, y.clamp(top, bottom) - y);
+                let distance = dx.hypot(dy);
+                if distance > radius {
+                    return None;
+                }
+                if distance > f64::EPSILON {
+                    return Some((dx / distance, dy / distance));
+                }
+                let (center_x, center_y) = object.center();
+                let (dx, dy) = (center_x - x, center_y - y);
+                let distance = dx.hypot(dy);
+                if distance > f64::EPSILON {
+                    Some((dx / distance, dy / distance))
+                } else {
+                    Some((0.0, -1.0))
+                }
+            }
+        }
+    }
+
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera, color: Color) {
+        let bounds = match *self {
+            Obstacle::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => (x, y, width, height),
+            Obstacle::Circle { x, y, radius } => {
+                (x - radius, y - radius, radius * 2.0, radius * 2.0)
+            }
+        };
+        let shape = match self {
+            Obstacle::Rect { .. } => Shape::Square,
+            Obstacle::Circle { .. } => Shape::Circle,
+        };
+        shape.draw(canvas, camera.to_screen_rect(bounds), 0.0, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::square;
+
+    #[test]
+    fn rect_pushes_back_through_the_shallowest_side() {
+        let rect = Obstacle::Rect {
+            x: 100.0,
+            y: 100.0,
+            width: 50.0,
+            height: 50.0,
+        };
+        assert_eq!(rect.normal(&square(0.0, 0.0, 10)), None);
+        assert_eq!(rect.normal(&square(92.0, 120.0, 10)), Some((-1.0, 0.0)));
+        assert_eq!(rect.normal(&square(148.0, 120.0, 10)), Some((1.0, 0.0)));
+        assert_eq!(rect.normal(&square(120.0, 91.0, 10)), Some((0.0, -1.0)));
+        assert_eq!(rect.normal(&square(120.0, 149.0, 10)), Some((0.0, 1.0)));
+    }
+
+    #[test]
+    fn circle_pushes_away_from_its_centre() {
+        let circle = Obstacle::Circle {
+            x: 100.0,
+            y: 100.0,
+            radius: 20.0,
+        };
+        assert_eq!(circle.normal(&square(0.0, 0.0, 10)), None);
+        assert_eq!(circle.normal(&square(115.0, 95.0, 10)), Some((1.0, 0.0)));
+        // 角だけが円に触れている
+        let (nx, ny) = circle.normal(&square(112.0, 112.0, 10)).unwrap();
+        assert!((nx - ny).abs() < 1e-9 && nx > 0.0);
+        // 中心が物体の内側にあるときは物体の中心から押し出す
+        assert_eq!(circle.normal(&square(95.0, 80.0, 10)), Some((0.0, -1.0)));
+    }
+}
@@ -0,0 +1,82 @@
+use std::f64::consts::TAU;
+
+use rand::Rng;
+use sdl2::pixels::Color;
+use sdl2::render::{BlendMode, Canvas, RenderTarget};
+
+use crate::camera::Camera;
+
+const BURST_SIZE: usize = 24;
+const PARTICLE_SIZE: f64 = 3.0;
+const DRAG: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    x: f64,
+    y: f64,
+    velocity_x: f64,
+    velocity_y: f64,
+    life: u32,
+    max_life: u32,
+    color: Color,
+}
+
+/// Purely visual particles. They are not part of the world physics and do not
+/// collide with objects.
+#[derive(Debug, Clone, Default)]
+pub struct Particles {
+    particles: Vec<Particle>,
+}
+
+impl Particles {
+    pub fn burst(&mut self, (x, y): (f64, f64), color: Color) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..BURST_SIZE {
+            let angle = rng.gen_range(0.0..TAU);
+            let speed = rng.gen_range(0.5..2.5);
+            let max_life = rng.gen_range(30..60);
+            self.particles.push(Particle {
+                x,
+                y,
+                velocity_x: angle.cos() * speed,
+                velocity_y: angle.sin() * speed,
+                life: max_life,
+                max_life,
+                color,
+            });
+        }
+    }
+
+    pub fn next_frame(&mut self) {
+        for particle in self.particles.iter_mut() {
+            particle.x += particle.velocity_x;
+            particle.y += particle.velocity_y;
+            particle.velocity_x *= DRAG;
+            particle.velocity_y *= DRAG;
+            particle.life -= 1;
+        }
+        self.particles.retain(|particle| particle.life > 0);
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera) {
+        canvas.set_blend_mode(BlendMode::Blend);
+        for particle in self.particles.iter() {
+            let alpha = (255 * particle.life / particle.max_life) as u8;
+            let color = particle.color;
+            canvas.set_draw_color(Color::RGBA(color.r, color.g, color.b, alpha));
+            canvas
+                .fill_rect(camera.to_screen_rect((
+                    particle.x,
+                    particle.y,
+                    PARTICLE_SIZE,
+                    PARTICLE_SIZE,
+                )))
+                .expect("failed to draw particle");
+        }
+        canvas.set_blend_mode(BlendMode::None);
+    }
+}
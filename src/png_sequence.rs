@@ -0,0 +1,37 @@
+use std::fs;
+use std::path::PathBuf;
+
+use sdl2::image::SaveSurface;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+
+use crate::frame::{Frame, FrameSink};
+
+/// Writes frames as `frame-000000.png`, `frame-000001.png`, ... into a directory.
+pub struct PngSequence {
+    dir: PathBuf,
+    index: usize,
+}
+
+impl PngSequence {
+    pub fn new(dir: PathBuf) -> Result<PngSequence, String> {
+        fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        Ok(PngSequence { dir, index: 0 })
+    }
+}
+
+impl FrameSink for PngSequence {
+    fn write_frame(&mut self, frame: &Frame) -> Result<(), String> {
+        let mut pixels = frame.pixels().to_vec();
+        let surface = Surface::from_data(
+            &mut pixels,
+            frame.width(),
+            frame.height(),
+            frame.width() * 3,
+            PixelFormatEnum::RGB24,
+        )?;
+        surface.save(self.dir.join(format!("frame-{:06}.png", self.index)))?;
+        self.index += 1;
+        Ok(())
+    }
+}
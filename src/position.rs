@@ -1,6 +1,6 @@
-use std::ops::Range;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Position {
     x: f64,
     y: f64,
@@ -13,12 +13,6 @@ impl Position {
         Position { x, y, max_x, max_y }
     }
 
-    pub fn random(range_x: Range<f64>, range_y: Range<f64>, max_x: f64, max_y: f64) -> Position {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        Self::new(rng.gen_range(range_x), rng.gen_range(range_y), max_x, max_y)
-    }
-
     #[inline]
     pub fn x(&self) -> f64 {
         self.x
@@ -29,6 +23,26 @@ impl Position {
         self.y
     }
 
+    /// Same position shifted without clamping, e.g. for drawing a wrapped copy.
+    pub fn offset(&self, x: f64, y: f64) -> Position {
+        Self::new(self.x + x, self.y + y, self.max_x, self.max_y)
+    }
+
+    /// Adjusts for an object growing by `width` x `height` around its centre,
+    /// keeping it inside the arena.
+    pub fn grow(&mut self, width: f64, height: f64) {
+        self.max_x -= width;
+        self.max_y -= height;
+        self.x = (self.x - width / 2.0).clamp(0.0, self.max_x.max(0.0));
+        self.y = (self.y - height / 2.0).clamp(0.0, self.max_y.max(0.0));
+    }
+
+    /// Moves on a torus of the given size instead of stopping at the edges.
+    pub fn move_wrapped(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.x = (self.x + x).rem_euclid(width);
+        self.y = (self.y + y).rem_euclid(height);
+    }
+
     pub fn move_distance(&mut self, x: f64, y: f64) {
         let new_x = self.x + x;
         let new_y = self.y + y;
@@ -1,5 +0,0 @@
-fn sample() {
-    sample2()
-}
-
-fn sample2(a: u8, b: u8, c: u8, d: u8) {}
@@ -1,53 +1,308 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
-use std::ops::Deref;
+use std::collections::{BTreeMap, HashMap};
 
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::render::Canvas;
+use sdl2::render::{BlendMode, Canvas, RenderTarget, TextureCreator};
 use sdl2::ttf::Font;
-use sdl2::video::Window;
 use uuid::Uuid;
 
-use crate::screen_object::ScreenObject;
+use crate::animation::ConversionAnimation;
+use crate::arena::Arena;
+use crate::assets::Assets;
+use crate::behavior::{Behavior, Neighbors};
+use crate::boundary::Boundary;
+use crate::camera::Camera;
+use crate::forces::Forces;
+use crate::grid::SpatialGrid;
+use crate::object::Object;
+use crate::obstacle::Obstacle;
+use crate::particle::Particles;
+use crate::screen_object::{Conversion, ScreenObject};
+use crate::snapshot::{ObjectSnapshot, Snapshot};
+use crate::species::{ConversionPolicy, SpeciesId, SpeciesRegistry};
+use crate::trail::Trail;
 
-pub struct Screen<'a, 'r, 'ttf_module, 'rwops> {
+pub struct Screen {
     width: u32,
     height: u32,
-    objects: HashMap<Uuid, RefCell<ScreenObject<'a, 'r>>>,
-    font: Font<'ttf_module, 'rwops>,
+    /// 衝突や力の適用順を ID 順に固定し、スナップショットから同じ試合を再現できるようにする
+    objects: BTreeMap<Uuid, RefCell<ScreenObject>>,
+    species: SpeciesRegistry,
+    tick: u64,
+    rng: Pcg64,
+    trails: HashMap<Uuid, Trail>,
+    trails_enabled: bool,
+    animations: HashMap<Uuid, ConversionAnimation>,
+    particles: Particles,
+    forces: Forces,
+    behaviors: HashMap<SpeciesId, Box<dyn Behavior + Send>>,
+    obstacles: Vec<Obstacle>,
+    boundary: Boundary,
+    arena: Arena,
+    conversion_policy: ConversionPolicy,
 }
 
-impl<'a, 'r, 'ttf_module, 'rwops> Screen<'a, 'r, 'ttf_module, 'rwops> {
-    pub fn new(
-        width: u32,
-        height: u32,
-        font: Font<'ttf_module, 'rwops>,
-    ) -> Screen<'a, 'r, 'ttf_module, 'rwops> {
+impl Screen {
+    pub fn new(width: u32, height: u32, species: SpeciesRegistry) -> Screen {
         Screen {
             width,
             height,
-            objects: HashMap::new(),
-            font,
+            objects: BTreeMap::new(),
+            species,
+            tick: 0,
+            rng: Pcg64::seed_from_u64(rand::random()),
+            trails: HashMap::new(),
+            trails_enabled: false,
+            animations: HashMap::new(),
+            particles: Particles::default(),
+            forces: Forces::default(),
+            behaviors: HashMap::new(),
+            obstacles: Vec::new(),
+            boundary: Boundary::Walls,
+            arena: Arena::Rect,
+            conversion_policy: ConversionPolicy::Preserve,
         }
     }
 
-    pub fn add_object(&mut self, object: ScreenObject<'a, 'r>) -> Uuid {
+    pub fn set_trails_enabled(&mut self, enabled: bool) {
+        self.trails_enabled = enabled;
+        if !enabled {
+            self.trails.clear();
+        }
+    }
+
+    #[inline]
+    pub fn trails_enabled(&self) -> bool {
+        self.trails_enabled
+    }
+
+    pub fn set_forces(&mut self, forces: Forces) {
+        self.forces = forces;
+    }
+
+    pub fn set_behavior(&mut self, species: SpeciesId, behavior: Box<dyn Behavior + Send>) {
+        self.behaviors.insert(species, behavior);
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    pub fn set_arena(&mut self, arena: Arena) {
+        self.arena = arena;
+    }
+
+    pub fn set_conversion_policy(&mut self, policy: ConversionPolicy) {
+        self.conversion_policy = policy;
+    }
+
+    pub fn set_obstacles(&mut self, obstacles: Vec<Obstacle>) {
+        self.obstacles = obstacles;
+    }
+
+    pub fn add_obstacle(&mut self, obstacle: Obstacle) {
+        self.obstacles.push(obstacle);
+    }
+
+    #[inline]
+    pub fn species(&self) -> &SpeciesRegistry {
+        &self.species
+    }
+
+    pub fn species_mut(&mut self) -> &mut SpeciesRegistry {
+        &mut self.species
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn centers(&self) -> impl Iterator<Item = (SpeciesId, (f64, f64))> + '_ {
+        self.objects.values().map(|object| {
+            let object = object.borrow();
+            (object.species(), object.object().center())
+        })
+    }
+
+    pub fn object_center(&self, id: Uuid) -> Option<(f64, f64)> {
+        self.objects
+            .get(&id)
+            .map(|object| object.borrow().object().center())
+    }
+
+    /// The object drawn at world point `(x, y)`, if any.
+    pub fn object_at(&self, (x, y): (f64, f64)) -> Option<Uuid> {
+        self.objects.iter().find_map(|(id, object)| {
+            let object = object.borrow();
+            let ((left, right), (top, bottom)) =
+                (object.object().range_x(), object.object().range_y());
+            (left <= x && x <= right && top <= y && y <= bottom).then_some(*id)
+        })
+    }
+
+    /// Centroid of the smallest species that still has objects.
+    pub fn survivors_center(&self) -> Option<(f64, f64)> {
+        let (index, _) = self
+            .object_count()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .min_by_key(|(_, count)| *count)?;
+        let centers = self
+            .objects
+            .values()
+            .map(|object| object.borrow())
+            .filter(|object| object.species().index() == index)
+            .map(|object| object.object().center())
+            .collect::<Vec<_>>();
+        let (sum_x, sum_y) = centers
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        Some((sum_x / centers.len() as f64, sum_y / centers.len() as f64))
+    }
+
+    /// Whether `object` could be placed without touching the arena edge, an
+    /// obstacle or another object.
+    pub fn is_free(&self, object: &Object) -> bool {
+        self.arena.contains(object)
+            && self
+                .obstacles
+                .iter()
+                .all(|obstacle| obstacle.normal(object).is_none())
+            && self.objects.values().all(|other| {
+                // is_collide_with は片側しか見ないので、内側に収まる場合も両方向で確かめる
+                let other = other.borrow().object();
+                !self.overlaps(object, &other) && !self.overlaps(&other, object)
+            })
+    }
+
+    #[inline]
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn rng_mut(&mut self) -> &mut Pcg64 {
+        &mut self.rng
+    }
+
+    pub fn add_object(&mut self, object: ScreenObject) -> Uuid {
         let id = Uuid::new_v4();
         self.objects.insert(id, RefCell::new(object));
         id
     }
 
+    pub fn snapshot(&self) -> Snapshot {
+        let objects = self
+            .objects
+            .iter()
+            .map(|(id, object)| {
+                let object = object.borrow();
+                ObjectSnapshot::new(*id, object.species(), object.object())
+            })
+            .collect::<Vec<_>>();
+        Snapshot::new(
+            self.species
+                .iter()
+                .map(|(_, species)| species.name().to_string())
+                .collect(),
+            self.width,
+            self.height,
+            self.tick,
+            self.rng.clone(),
+            objects,
+            self.obstacles.clone(),
+        )
+    }
+
+    /// Fails if the snapshot refers to species this registry does not have or
+    /// holds an object no constructor would accept.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), String> {
+        let names = self
+            .species
+            .iter()
+            .map(|(_, species)| species.name().to_string())
+            .collect::<Vec<_>>();
+        if !snapshot.species().is_empty() && snapshot.species() != names.as_slice() {
+            return Err(format!(
+                "saved with species {} but running with {}",
+                snapshot.species().join(", "),
+                names.join(", ")
+            ));
+        }
+        for object in snapshot.objects() {
+            if object.species().index() >= names.len() {
+                return Err(format!(
+                    "object {} has unknown species {}",
+                    object.id(),
+                    object.species().index()
+                ));
+            }
+            let mass = object.object().mass();
+            if !(mass > 0.0 && mass.is_finite()) {
+                return Err(format!(
+                    "object {} must have a mass greater than 0",
+                    object.id()
+                ));
+            }
+        }
+
+        self.width = snapshot.width();
+        self.height = snapshot.height();
+        self.tick = snapshot.tick();
+        self.rng = snapshot.rng().clone();
+        self.obstacles = snapshot.obstacles().to_vec();
+        self.objects.clear();
+        self.trails.clear();
+        self.animations.clear();
+        self.particles.clear();
+        for object in snapshot.objects() {
+            let screen_object = ScreenObject::new(object.species(), object.object());
+            self.objects
+                .insert(object.id(), RefCell::new(screen_object));
+        }
+        Ok(())
+    }
+
     pub fn next_frame(&mut self) {
-        let mut collisions = HashMap::new();
+        if !self.forces.is_empty() {
+            for object in self.objects.values_mut() {
+                self.forces
+                    .apply(object.get_mut().object_mut(), &mut self.rng);
+            }
+        }
+
+        if !self.behaviors.is_empty() {
+            self.steer();
+        }
+
+        let wrap = self.wrap();
+        let mut collisions = BTreeMap::new();
 
-        let mut checked_objects = HashMap::<Uuid, ScreenObject>::new();
+        let mut checked_objects = SpatialGrid::new(
+            self.cell_size(),
+            self.width as f64,
+            self.height as f64,
+            wrap,
+        );
         for (id, object) in self.objects.iter() {
             let object = object.borrow();
             let mut collision = Collision::None;
 
             // 壁との衝突判定
-            if object.object().position().x() <= 0.0 {
+            if wrap.is_some() {
+                // トーラスには壁がない
+            } else if let Some((nx, ny)) = self.arena.wall_normal(&object.object()) {
+                collision = Collision::Surface(nx, ny);
+            } else if object.object().position().x() <= 0.0 {
                 collision = Collision::LeftWall;
             } else if object.object().position().x() + object.object().width() as f64
                 >= self.width as f64
@@ -61,57 +316,212 @@ impl<'a, 'r, 'ttf_module, 'rwops> Screen<'a, 'r, 'ttf_module, 'rwops> {
                 collision = Collision::BottomWall;
             }
 
+            // 障害物との衝突判定
+            for obstacle in self.obstacles.iter() {
+                if let Some((nx, ny)) = obstacle.normal(&object.object()) {
+                    collision = Collision::Surface(nx, ny);
+                }
+            }
+
             // 他のオブジェクトとの衝突判定
-            for (checked_other_id, checked_other_object) in checked_objects.iter() {
-                if object
-                    .object()
-                    .is_collide_with(&checked_other_object.object())
-                {
+            for (checked_other_id, checked_other_object) in
+                checked_objects.nearby(object.object().center())
+            {
+                if self.overlaps(&object.object(), checked_other_object) {
                     collision = Collision::Object(*checked_other_id);
                 }
             }
 
-            checked_objects.insert(*id, *object.deref());
+            checked_objects.insert(object.object().center(), (*id, object.object()));
             collisions.insert(*id, collision);
         }
 
+        let mut conversions = Vec::new();
         for (id, collision) in collisions.iter() {
             match collision {
                 Collision::None => {
                     let object = self.objects.get_mut(id).unwrap().get_mut().object_mut();
-                    object.next_frame();
+                    object.next_frame(wrap);
                 }
                 Collision::LeftWall | Collision::RightWall => {
                     let object = self.objects.get_mut(id).unwrap().get_mut().object_mut();
                     object.velocity_mut().reverse_x();
-                    object.next_frame();
+                    object.next_frame(wrap);
                 }
                 Collision::TopWall | Collision::BottomWall => {
                     let object = self.objects.get_mut(id).unwrap().get_mut().object_mut();
                     object.velocity_mut().reverse_y();
-                    object.next_frame();
+                    object.next_frame(wrap);
+                }
+                Collision::Surface(nx, ny) => {
+                    let object = self.objects.get_mut(id).unwrap().get_mut().object_mut();
+                    object.velocity_mut().reflect(*nx, *ny);
+                    object.next_frame(wrap);
                 }
                 Collision::Object(other_id) => {
                     let (mut self_object, mut other_object) = (
                         self.objects.get(id).unwrap().borrow_mut(),
                         self.objects.get(other_id).unwrap().borrow_mut(),
                     );
-                    self_object.collide(&mut other_object);
-                    self_object.object_mut().next_frame();
-                    other_object.object_mut().next_frame();
+                    let conversion = self_object.collide(&mut other_object, &self.species);
+                    self_object.object_mut().next_frame(wrap);
+                    other_object.object_mut().next_frame(wrap);
+                    match conversion {
+                        Some(Conversion::Own(species)) => {
+                            conversions.push((*id, self_object.object().center(), species));
+                        }
+                        Some(Conversion::Other(species)) => {
+                            conversions.push((*other_id, other_object.object().center(), species));
+                        }
+                        None => {}
+                    }
                 }
             }
         }
+
+        // 変換されたオブジェクトのアニメーションとパーティクル
+        self.animations
+            .retain(|_, animation| !animation.is_finished(self.tick));
+        for (id, center, species) in conversions {
+            let traits = self.species.get(species);
+            if self.conversion_policy == ConversionPolicy::Adopt {
+                let object = self.objects.get_mut(&id).unwrap().get_mut().object_mut();
+                object.reshape(traits.size(), traits.size(), traits.mass());
+                object.velocity_mut().set_magnitude(traits.speed());
+                object.set_speed_limit(traits.speed());
+            }
+            let color = traits.color();
+            self.animations
+                .insert(id, ConversionAnimation::new(self.tick, color));
+            self.particles.burst(center, color);
+        }
+        self.particles.next_frame();
+
+        if self.trails_enabled {
+            for (id, object) in self.objects.iter() {
+                let object = object.borrow();
+                self.trails.entry(*id).or_default().push(
+                    object.object().center(),
+                    self.species.get(object.species()).color(),
+                );
+            }
+        }
+
+        self.tick += 1;
+    }
+
+    fn wrap(&self) -> Option<(f64, f64)> {
+        match self.boundary {
+            Boundary::Walls => None,
+            Boundary::Wrap => Some((self.width as f64, self.height as f64)),
+        }
+    }
+
+    /// `object.is_collide_with(other)`, including through a wrapped edge.
+    /// Either of the two may be the one sticking out over the edge.
+    fn overlaps(&self, object: &Object, other: &Object) -> bool {
+        self.images(other)
+            .any(|image| object.is_collide_with(&image))
+            || self
+                .images(object)
+                .skip(1)
+                .any(|image| image.is_collide_with(other))
+    }
+
+    /// Side of the collision grid's cells: the largest object dimension.
+    fn cell_size(&self) -> f64 {
+        self.objects
+            .values()
+            .map(|object| {
+                let object = object.borrow().object();
+                object.width().max(object.height())
+            })
+            .max()
+            .unwrap_or(1)
+            .max(1) as f64
+    }
+
+    /// The object plus, on a torus, the copies of it that show through the
+    /// edges it overlaps.
+    fn images(&self, object: &Object) -> impl Iterator<Item = Object> {
+        let (dx, dy) = match self.wrap() {
+            Some((width, height)) => (
+                seam_offset(object.range_x(), width),
+                seam_offset(object.range_y(), height),
+            ),
+            None => (0.0, 0.0),
+        };
+        let object = *object;
+        [
+            (0.0, 0.0, true),
+            (dx, 0.0, dx != 0.0),
+            (0.0, dy, dy != 0.0),
+            (dx, dy, dx != 0.0 && dy != 0.0),
+        ]
+        .into_iter()
+        .filter(|&(_, _, shown)| shown)
+        .map(move |(x, y, _)| {
+            let mut image = object;
+            image.translate(x, y);
+            image
+        })
+    }
+
+    fn steer(&mut self) {
+        let objects = self
+            .objects
+            .iter()
+            .map(|(id, object)| (*id, *object.borrow()))
+            .collect::<Vec<_>>();
+        let perception = self
+            .behaviors
+            .values()
+            .map(|behavior| behavior.perception())
+            .fold(f64::MIN_POSITIVE, f64::max);
+        let mut grid = SpatialGrid::new(
+            perception,
+            self.width as f64,
+            self.height as f64,
+            self.wrap(),
+        );
+        for (index, (_, object)) in objects.iter().enumerate() {
+            grid.insert(object.object().center(), index);
+        }
+        for (id, object) in objects.iter() {
+            let Some(behavior) = self.behaviors.get(&object.species()) else {
+                continue;
+            };
+            let neighbors = Neighbors::new(*id, object, &objects, &grid, &self.species);
+            let (ax, ay) = behavior.acceleration(object, &neighbors);
+            let max_speed = object
+                .object()
+                .speed_limit()
+                .unwrap_or_else(|| self.species.get(object.species()).speed());
+            let velocity = self
+                .objects
+                .get_mut(id)
+                .unwrap()
+                .get_mut()
+                .object_mut()
+                .velocity_mut();
+            velocity.accelerate(ax, ay);
+            velocity.limit(max_speed);
+        }
     }
 
-    fn draw_text(&mut self, canvas: &mut Canvas<Window>, text: &str) {
-        let surface = self
-            .font
+    fn draw_text<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        texture_creator: &TextureCreator<T::Context>,
+        font: &Font,
+        color: Color,
+        text: &str,
+    ) {
+        let surface = font
             .render(text)
-            .blended(Color::RGBA(255, 255, 255, 255))
+            .blended(color)
             .map_err(|e| e.to_string())
             .unwrap();
-        let texture_creator = canvas.texture_creator();
         let font_texture = texture_creator
             .create_texture_from_surface(&surface)
             .map_err(|e| e.to_string())
@@ -128,46 +538,230 @@ impl<'a, 'r, 'ttf_module, 'rwops> Screen<'a, 'r, 'ttf_module, 'rwops> {
             .unwrap();
     }
 
-    fn object_count(&self) -> (usize, usize, usize) {
-        let (mut rock_count, mut paper_count, mut scissors_count) = (0, 0, 0);
+    fn object_count(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.species.iter().count()];
         for object in self.objects.values() {
-            match object.borrow().deref() {
-                ScreenObject::Paper(_) => paper_count += 1,
-                ScreenObject::Rock(_) => rock_count += 1,
-                ScreenObject::Scissors(_) => scissors_count += 1,
-            }
+            counts[object.borrow().species().index()] += 1;
         }
-        (rock_count, paper_count, scissors_count)
+        counts
     }
 
-    pub fn draw(&mut self, canvas: &mut Canvas<Window>) {
+    pub fn draw<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        texture_creator: &TextureCreator<T::Context>,
+        assets: &Assets,
+        camera: &Camera,
+    ) {
+        self.arena
+            .draw(canvas, camera, assets.theme().obstacle_color());
+        for obstacle in self.obstacles.iter() {
+            obstacle.draw(canvas, camera, assets.theme().obstacle_color());
+        }
+        for trail in self.trails.values() {
+            trail.draw(canvas, camera);
+        }
+        for (id, object) in self.objects.iter() {
+            for image in self.images(&object.borrow().object()) {
+                let mut object = *object.borrow();
+                *object.object_mut() = image;
+                match self.animations.get(id) {
+                    Some(animation) => {
+                        let scale = animation.scale(self.tick);
+                        canvas.set_blend_mode(BlendMode::Blend);
+                        canvas.set_draw_color(animation.flash_color(self.tick));
+                        canvas
+                            .fill_rect(camera.to_screen_rect(object.object().bounds(scale)))
+                            .unwrap();
+                        canvas.set_blend_mode(BlendMode::None);
+                        object.draw(canvas, camera, assets, &self.species, scale);
+                    }
+                    None => object.draw(canvas, camera, assets, &self.species, 1.0),
+                }
+            }
+        }
+        self.particles.draw(canvas, camera);
+
+        // HUD はカメラの影響を受けない
+        let text = self
+            .species
+            .iter()
+            .zip(self.object_count())
+            .map(|((_, species), count)| format!("{}: {}", species.name(), count))
+            .collect::<Vec<_>>()
+            .join(", ");
         self.draw_text(
             canvas,
-            format!(
-                "Rock: {}, Paper: {}, Scissors: {}",
-                self.object_count().0,
-                self.object_count().1,
-                self.object_count().2
-            )
-            .as_str(),
+            texture_creator,
+            assets.font(),
+            assets.theme().text_color(),
+            &text,
         );
-        self.next_frame();
-        for object in self.objects.values_mut() {
-            match object.borrow().deref() {
-                ScreenObject::Paper(paper) => paper.draw(canvas),
-                ScreenObject::Rock(rock) => rock.draw(canvas),
-                ScreenObject::Scissors(scissors) => scissors.draw(canvas),
-            }
-        }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// シミュレーションを別スレッドに渡せるように、Screen は SDL のリソースを持たない
+const _: fn() = || {
+    fn assert_send<T: Send + 'static>() {}
+    assert_send::<Screen>();
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum Collision {
     None,
     TopWall,
     BottomWall,
     LeftWall,
     RightWall,
+    Surface(f64, f64),
     Object(Uuid),
 }
+
+/// How far a copy of an object spanning `range` must move to show through
+/// the opposite edge of a torus `size` wide, or 0 if it does not cross one.
+fn seam_offset((low, high): (f64, f64), size: f64) -> f64 {
+    if high > size {
+        -size
+    } else if low < 0.0 {
+        size
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Position;
+    use crate::shape::Shape;
+    use crate::species::Species;
+    use crate::velocity::Velocity;
+
+    fn screen() -> Screen {
+        let mut species = SpeciesRegistry::default();
+        for name in ["Rock", "Paper", "Scissors"] {
+            species.register(Species::new(
+                name,
+                Color::RGB(255, 255, 255),
+                Shape::Circle,
+                "",
+                1.1,
+                1.0,
+                25,
+            ));
+        }
+        species.add_prey(SpeciesId::ROCK, SpeciesId::SCISSORS);
+        species.add_prey(SpeciesId::PAPER, SpeciesId::ROCK);
+        species.add_prey(SpeciesId::SCISSORS, SpeciesId::PAPER);
+        Screen::new(800, 600, species)
+    }
+
+    fn object(x: f64, y: f64, size: u32, vx: f64) -> Object {
+        Object::new(
+            Position::new(x, y, 800.0 - size as f64, 600.0 - size as f64),
+            size,
+            size,
+            1.0,
+            Velocity::new(vx, 0.0),
+        )
+    }
+
+    #[test]
+    fn objects_collide_across_the_wrapped_edge() {
+        let mut screen = screen();
+        screen.set_boundary(Boundary::Wrap);
+        // 右端をまたいでいる岩と左端のハサミ
+        screen.add_object(ScreenObject::new(
+            SpeciesId::ROCK,
+            object(790.0, 300.0, 25, 1.0),
+        ));
+        let scissors = screen.add_object(ScreenObject::new(
+            SpeciesId::SCISSORS,
+            object(5.0, 300.0, 25, -1.0),
+        ));
+        screen.next_frame();
+        assert_eq!(
+            screen.objects[&scissors].borrow().species(),
+            SpeciesId::ROCK
+        );
+    }
+
+    #[test]
+    fn resumed_snapshots_replay_the_same_match() {
+        let mut screen = screen();
+        screen.set_forces(Forces::new(0.0, None, 0.3).unwrap());
+        crate::spawn::spawn(
+            &mut screen,
+            &crate::spawn::Layout::Uniform,
+            60,
+            &HashMap::new(),
+        )
+        .unwrap();
+        for _ in 0..50 {
+            screen.next_frame();
+        }
+        let snapshot = screen.snapshot();
+
+        let mut replays = Vec::new();
+        for _ in 0..2 {
+            let mut replay = self::screen();
+            replay.set_forces(Forces::new(0.0, None, 0.3).unwrap());
+            replay.restore(&snapshot).unwrap();
+            for _ in 0..300 {
+                replay.next_frame();
+            }
+            replays.push(serde_json::to_string(&replay.snapshot()).unwrap());
+        }
+        assert_eq!(replays[0], replays[1]);
+    }
+
+    #[test]
+    fn restore_rejects_objects_the_registry_cannot_hold() {
+        let mut screen = screen();
+        screen.add_object(ScreenObject::new(
+            SpeciesId::ROCK,
+            object(100.0, 100.0, 25, 1.0),
+        ));
+        let json = serde_json::to_string(&screen.snapshot()).unwrap();
+
+        let unknown = json.replace(r#""species":0"#, r#""species":3"#);
+        let snapshot = serde_json::from_str::<Snapshot>(&unknown).unwrap();
+        assert!(screen.restore(&snapshot).is_err());
+
+        let renamed = json.replace(r#""Rock""#, r#""Lizard""#);
+        let snapshot = serde_json::from_str::<Snapshot>(&renamed).unwrap();
+        assert!(screen.restore(&snapshot).is_err());
+
+        let weightless = json.replace(r#""mass":1.0"#, r#""mass":0.0"#);
+        let snapshot = serde_json::from_str::<Snapshot>(&weightless).unwrap();
+        assert!(screen.restore(&snapshot).is_err());
+
+        let snapshot = serde_json::from_str::<Snapshot>(&json).unwrap();
+        assert!(screen.restore(&snapshot).is_ok());
+    }
+
+    #[test]
+    fn overlaps_through_the_edge_whichever_object_crosses_it() {
+        let mut screen = screen();
+        screen.set_boundary(Boundary::Wrap);
+        let crossing = object(790.0, 300.0, 25, 0.0);
+        let inside = object(5.0, 300.0, 25, 0.0);
+        assert!(screen.overlaps(&crossing, &inside));
+        assert!(screen.overlaps(&inside, &crossing));
+        assert!(!screen.overlaps(&crossing, &object(400.0, 300.0, 25, 0.0)));
+
+        screen.set_boundary(Boundary::Walls);
+        assert!(!screen.overlaps(&crossing, &inside));
+    }
+
+    #[test]
+    fn no_room_inside_a_larger_object() {
+        let mut screen = screen();
+        screen.add_object(ScreenObject::new(
+            SpeciesId::ROCK,
+            object(100.0, 100.0, 60, 0.0),
+        ));
+        assert!(!screen.is_free(&object(110.0, 110.0, 10, 0.0)));
+        assert!(screen.is_free(&object(300.0, 100.0, 10, 0.0)));
+    }
+}
@@ -1,66 +1,96 @@
 use log::debug;
+use sdl2::render::{Canvas, RenderTarget};
 
+use crate::assets::Assets;
+use crate::camera::Camera;
 use crate::object::Object;
-use crate::paper::Paper;
-use crate::rock::Rock;
-use crate::scissors::Scissors;
+use crate::species::{SpeciesId, SpeciesRegistry};
 
-#[derive(Clone, Copy)]
-pub enum ScreenObject<'a, 'r> {
-    Paper(Paper<'a, 'r>),
-    Rock(Rock<'a, 'r>),
-    Scissors(Scissors<'a, 'r>),
+/// The result of a battle that converted one of the two objects, carrying the
+/// species it was converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    Own(SpeciesId),
+    Other(SpeciesId),
 }
 
-impl<'a, 'r> ScreenObject<'a, 'r> {
-    fn battle(&mut self, other: &mut ScreenObject) {
-        match (&self, &other) {
-            (ScreenObject::Paper(_), ScreenObject::Rock(rock)) => {
-                debug!("Paper wins Rock");
-                *other = ScreenObject::Paper(Paper::from(*rock));
-            }
-            (ScreenObject::Rock(_), ScreenObject::Scissors(scissors)) => {
-                debug!("Rock wins Scissors");
-                *other = ScreenObject::Rock(Rock::from(*scissors));
-            }
-            (ScreenObject::Scissors(_), ScreenObject::Paper(paper)) => {
-                debug!("Scissors wins Paper");
-                *other = ScreenObject::Scissors(Scissors::from(*paper));
-            }
-            (ScreenObject::Paper(paper), ScreenObject::Scissors(_)) => {
-                debug!("Paper loses Scissors");
-                *self = ScreenObject::Scissors(Scissors::from(*paper));
-            }
-            (ScreenObject::Rock(rock), ScreenObject::Paper(_)) => {
-                debug!("Rock loses Paper");
-                *self = ScreenObject::Paper(Paper::from(*rock));
-            }
-            (ScreenObject::Scissors(scissors), ScreenObject::Rock(_)) => {
-                debug!("Scissors loses Rock");
-                *self = ScreenObject::Rock(Rock::from(*scissors));
-            }
-            _ => {}
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenObject {
+    species: SpeciesId,
+    object: Object,
+}
+
+impl ScreenObject {
+    pub fn new(species: SpeciesId, object: Object) -> Self {
+        Self { species, object }
+    }
+
+    fn battle(
+        &mut self,
+        other: &mut ScreenObject,
+        registry: &SpeciesRegistry,
+    ) -> Option<Conversion> {
+        let (own_name, other_name) = (
+            registry.get(self.species).name(),
+            registry.get(other.species).name(),
+        );
+        if registry.beats(self.species, other.species) {
+            debug!("{} wins {}", own_name, other_name);
+            other.species = self.species;
+            Some(Conversion::Other(self.species))
+        } else if registry.beats(other.species, self.species) {
+            debug!("{} loses {}", own_name, other_name);
+            self.species = other.species;
+            Some(Conversion::Own(other.species))
+        } else {
+            None
         }
     }
 
+    #[inline]
+    pub fn species(&self) -> SpeciesId {
+        self.species
+    }
+
+    #[inline]
     pub fn object(&self) -> Object {
-        match self {
-            ScreenObject::Paper(paper) => *paper.object(),
-            ScreenObject::Rock(rock) => *rock.object(),
-            ScreenObject::Scissors(scissors) => *scissors.object(),
-        }
+        self.object
     }
 
+    #[inline]
     pub fn object_mut(&mut self) -> &mut Object {
-        match self {
-            ScreenObject::Paper(paper) => paper.object_mut(),
-            ScreenObject::Rock(rock) => rock.object_mut(),
-            ScreenObject::Scissors(scissors) => scissors.object_mut(),
-        }
+        &mut self.object
     }
 
-    pub fn collide(&mut self, other: &mut ScreenObject<'a, 'r>) {
-        self.battle(other);
-        self.object_mut().collide(other.object_mut());
+    pub fn collide(
+        &mut self,
+        other: &mut ScreenObject,
+        registry: &SpeciesRegistry,
+    ) -> Option<Conversion> {
+        let conversion = self.battle(other, registry);
+        self.object.collide(&mut other.object);
+        conversion
+    }
+
+    pub fn draw<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        camera: &Camera,
+        assets: &Assets,
+        registry: &SpeciesRegistry,
+        scale: f64,
+    ) {
+        let species = registry.get(self.species);
+        match species.texture() {
+            Some(texture) => self
+                .object
+                .draw(canvas, camera, assets.texture(texture), scale),
+            None => species.shape().draw(
+                canvas,
+                camera.to_screen_rect(self.object.bounds(scale)),
+                self.object.angle(),
+                species.color(),
+            ),
+        }
     }
 }
@@ -0,0 +1,165 @@
+use std::f64::consts::PI;
+
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{Canvas, RenderTarget};
+
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// Primitive used to draw a species when it has no sprite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Circle,
+    Square,
+    Triangle,
+}
+
+impl Shape {
+    /// Draws the shape filling `rect`, turned clockwise by `angle` degrees.
+    pub fn draw<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        rect: Rect,
+        angle: f64,
+        color: Color,
+    ) {
+        let vertices = self.vertices(rect, angle);
+        canvas.set_draw_color(color);
+        for (y, left, right) in scanlines(&vertices) {
+            canvas
+                .draw_line(
+                    Point::new(left.round() as i32, y),
+                    Point::new(right.round() as i32, y),
+                )
+                .expect("failed to draw shape");
+        }
+        let outline = vertices
+            .iter()
+            .chain(vertices.first())
+            .map(|&(x, y)| Point::new(x.round() as i32, y.round() as i32))
+            .collect::<Vec<_>>();
+        canvas.set_draw_color(Color::RGB(color.r / 2, color.g / 2, color.b / 2));
+        canvas
+            .draw_lines(outline.as_slice())
+            .expect("failed to draw shape");
+    }
+
+    fn vertices(&self, rect: Rect, angle: f64) -> Vec<(f64, f64)> {
+        let (half_width, half_height) = (rect.width() as f64 / 2.0, rect.height() as f64 / 2.0);
+        let corners = match self {
+            Shape::Square => vec![
+                (-half_width, -half_height),
+                (half_width, -half_height),
+                (half_width, half_height),
+                (-half_width, half_height),
+            ],
+            Shape::Circle => (0..CIRCLE_SEGMENTS)
+                .map(|i| {
+                    let angle = 2.0 * PI * i as f64 / CIRCLE_SEGMENTS as f64;
+                    (half_width * angle.cos(), half_height * angle.sin())
+                })
+                .collect(),
+            // 頂点は上辺の中央、底辺は矩形の下端
+            Shape::Triangle => vec![
+                (0.0, -half_height),
+                (half_width, half_height),
+                (-half_width, half_height),
+            ],
+        };
+        let center = rect.center();
+        let (sin, cos) = angle.to_radians().sin_cos();
+        corners
+            .iter()
+            .map(|(x, y)| {
+                (
+                    center.x() as f64 + x * cos - y * sin,
+                    center.y() as f64 + x * sin + y * cos,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Horizontal spans `(y, left, right)` that fill a convex polygon.
+fn scanlines(vertices: &[(f64, f64)]) -> Vec<(i32, f64, f64)> {
+    let top = vertices.iter().map(|v| v.1).fold(f64::MAX, f64::min).ceil() as i32;
+    let bottom = vertices
+        .iter()
+        .map(|v| v.1)
+        .fold(f64::MIN, f64::max)
+        .floor() as i32;
+    let mut spans = Vec::new();
+    for row in top..=bottom {
+        let y = row as f64;
+        let (mut left, mut right) = (f64::MAX, f64::MIN);
+        for (i, &(ax, ay)) in vertices.iter().enumerate() {
+            let (bx, by) = vertices[(i + 1) % vertices.len()];
+            if (ay <= y && y <= by) || (by <= y && y <= ay) {
+                // 水平な辺は両端をそのまま使う
+                let (from, to) = if (by - ay).abs() <= f64::EPSILON {
+                    (ax.min(bx), ax.max(bx))
+                } else {
+                    let x = ax + (y - ay) * (bx - ax) / (by - ay);
+                    (x, x)
+                };
+                left = left.min(from);
+                right = right.max(to);
+            }
+        }
+        if left <= right {
+            spans.push((row, left, right));
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+    }
+
+    #[test]
+    fn vertices_fill_the_rect() {
+        let rect = Rect::new(10, 20, 30, 40);
+        assert_eq!(
+            Shape::Square.vertices(rect, 0.0),
+            vec![(10.0, 20.0), (40.0, 20.0), (40.0, 60.0), (10.0, 60.0)]
+        );
+        assert_eq!(
+            Shape::Triangle.vertices(rect, 0.0),
+            vec![(25.0, 20.0), (40.0, 60.0), (10.0, 60.0)]
+        );
+        let circle = Shape::Circle.vertices(rect, 0.0);
+        assert_eq!(circle.len(), CIRCLE_SEGMENTS);
+        assert!(circle
+            .iter()
+            .all(|&(x, y)| ((x - 25.0) / 15.0).hypot((y - 40.0) / 20.0) - 1.0 < 1e-9));
+    }
+
+    #[test]
+    fn vertices_turn_clockwise_about_the_centre() {
+        let rect = Rect::new(0, 0, 20, 10);
+        let turned = Shape::Triangle.vertices(rect, 90.0);
+        // 上を向いていた頂点が右を向く
+        assert!(close(turned[0], (15.0, 5.0)), "{:?}", turned[0]);
+        assert!(close(turned[1], (5.0, 15.0)), "{:?}", turned[1]);
+    }
+
+    #[test]
+    fn scanlines_cover_every_row_of_the_polygon() {
+        let square = Shape::Square.vertices(Rect::new(0, 0, 4, 4), 0.0);
+        assert_eq!(
+            scanlines(&square),
+            (0..=4).map(|y| (y, 0.0, 4.0)).collect::<Vec<_>>()
+        );
+
+        let triangle = Shape::Triangle.vertices(Rect::new(0, 0, 4, 4), 0.0);
+        let spans = scanlines(&triangle);
+        assert_eq!(spans.first(), Some(&(0, 2.0, 2.0)));
+        assert_eq!(spans[2], (2, 1.0, 3.0));
+        assert_eq!(spans.last(), Some(&(4, 0.0, 4.0)));
+    }
+}
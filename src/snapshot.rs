@@ -0,0 +1,125 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use rand_pcg::Pcg64;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::json::read_json;
+use crate::object::Object;
+use crate::obstacle::Obstacle;
+use crate::species::SpeciesId;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Registry order the species ids refer to; empty in older snapshots.
+    #[serde(default)]
+    species: Vec<String>,
+    width: u32,
+    height: u32,
+    tick: u64,
+    rng: Pcg64,
+    objects: Vec<ObjectSnapshot>,
+    #[serde(default)]
+    obstacles: Vec<Obstacle>,
+}
+
+impl Snapshot {
+    pub fn new(
+        species: Vec<String>,
+        width: u32,
+        height: u32,
+        tick: u64,
+        rng: Pcg64,
+        objects: Vec<ObjectSnapshot>,
+        obstacles: Vec<Obstacle>,
+    ) -> Snapshot {
+        Snapshot {
+            species,
+            width,
+            height,
+            tick,
+            rng,
+            objects,
+            obstacles,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Snapshot, String> {
+        read_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    #[inline]
+    pub fn species(&self) -> &[String] {
+        &self.species
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    #[inline]
+    pub fn rng(&self) -> &Pcg64 {
+        &self.rng
+    }
+
+    #[inline]
+    pub fn objects(&self) -> &[ObjectSnapshot] {
+        &self.objects
+    }
+
+    #[inline]
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ObjectSnapshot {
+    id: Uuid,
+    species: SpeciesId,
+    object: Object,
+}
+
+impl ObjectSnapshot {
+    pub fn new(id: Uuid, species: SpeciesId, object: Object) -> ObjectSnapshot {
+        ObjectSnapshot {
+            id,
+            species,
+            object,
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    #[inline]
+    pub fn species(&self) -> SpeciesId {
+        self.species
+    }
+
+    #[inline]
+    pub fn object(&self) -> Object {
+        self.object
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::path::Path;
+
+use log::warn;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::json::read_json;
+use crate::object::Object;
+use crate::position::Position;
+use crate::screen::Screen;
+use crate::screen_object::ScreenObject;
+use crate::species::SpeciesId;
+use crate::velocity::{SpeedDistribution, Velocity};
+
+/// Attempts per object before it is skipped rather than placed overlapping.
+const MAX_ATTEMPTS: u32 = 1000;
+/// Attempts at the layout's preferred spot before sampling the whole arena.
+const PREFERRED_ATTEMPTS: u32 = 100;
+
+/// How the initial objects are placed.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "layout", rename_all = "lowercase")]
+pub enum Layout {
+    /// Each species starts in its own corner.
+    Corners,
+    Uniform,
+    Grid,
+    /// One concentric ring per species around the centre.
+    Rings,
+    /// Each species starts in a round cluster at a random spot.
+    Blobs,
+    Regions {
+        regions: Vec<Region>,
+    },
+}
+
+/// Rectangle in which one species spawns.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Region {
+    species: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Layout {
+    pub fn parse(name: &str) -> Option<Layout> {
+        match name {
+            "corners" => Some(Layout::Corners),
+            "uniform" => Some(Layout::Uniform),
+            "grid" => Some(Layout::Grid),
+            "rings" => Some(Layout::Rings),
+            "blobs" => Some(Layout::Blobs),
+            _ => None,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Layout, String> {
+        read_json(path)
+    }
+}
+
+/// Places `count` objects, cycling through the species, so that none of them
+/// overlap each other, the obstacles or the arena edge. Species missing from
+/// `speeds` start at their trait speed.
+pub fn spawn(
+    screen: &mut Screen,
+    layout: &Layout,
+    count: usize,
+    speeds: &HashMap<SpeciesId, SpeedDistribution>,
+) -> Result<(), String> {
+    let species = screen
+        .species()
+        .iter()
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    let (width, height) = (screen.width() as f64, screen.height() as f64);
+
+    let regions = match layout {
+        Layout::Regions { regions } => regions
+            .iter()
+            .map(|region| {
+                let id = screen
+                    .species()
+                    .find(&region.species)
+                    .ok_or_else(|| format!("unknown species in region: {}", region.species))?;
+                Ok((id, region.clone()))
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        _ => Vec::new(),
+    };
+    let blobs = species
+        .iter()
+        .map(|_| {
+            let rng = screen.rng_mut();
+            (
+                rng.gen_range(width * 0.2..width * 0.8),
+                rng.gen_range(height * 0.2..height * 0.8),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for i in 0..count {
+        let kind = i % species.len();
+        let id = species[kind];
+        // 同じ種族の中での番号
+        let (slot, slots) = (i / species.len(), count.div_ceil(species.len()));
+        let (size, mass, speed) = {
+            let traits = screen.species().get(id);
+            (traits.size(), traits.mass(), traits.speed())
+        };
+        let (max_x, max_y) = (width - size as f64, height - size as f64);
+
+        let mut placed = None;
+        for attempt in 0..MAX_ATTEMPTS {
+            let (x, y) = if attempt < PREFERRED_ATTEMPTS {
+                let size = size as f64;
+                let rng = screen.rng_mut();
+                match layout {
+                    Layout::Corners => {
+                        let (corner_x, corner_y) =
+                            [(1.0, 0.0), (0.0, 0.0), (1.0, 1.0), (0.0, 1.0)][kind % 4];
+                        sample(
+                            rng,
+                            corner_x * width * 0.8,
+                            corner_y * height * 0.8,
+                            width * 0.2,
+                            height * 0.2,
+                            size,
+                        )
+                    }
+                    Layout::Uniform => sample(rng, 0.0, 0.0, width, height, size),
+                    Layout::Grid if attempt == 0 => {
+                        let columns = (count as f64).sqrt().ceil() as usize;
+                        let rows = count.div_ceil(columns);
+                        let (cell_width, cell_height) =
+                            (width / columns as f64, height / rows as f64);
+                        (
+                            (i % columns) as f64 * cell_width + (cell_width - size) / 2.0,
+                            (i / columns) as f64 * cell_height + (cell_height - size) / 2.0,
+                        )
+                    }
+                    Layout::Rings if attempt == 0 => {
+                        let max_radius = width.min(height) / 2.0 - size;
+                        let radius = max_radius * (kind + 1) as f64 / species.len() as f64;
+                        let angle = 2.0 * PI * slot as f64 / slots as f64;
+                        (
+                            width / 2.0 + radius * angle.cos() - size / 2.0,
+                            height / 2.0 + radius * angle.sin() - size / 2.0,
+                        )
+                    }
+                    Layout::Grid | Layout::Rings => sample(rng, 0.0, 0.0, width, height, size),
+                    Layout::Blobs => {
+                        // 入りきらなければ少しずつ広げる
+                        let radius = width.min(height) * 0.1 * (1.0 + attempt as f64 / 20.0);
+                        let (center_x, center_y) = blobs[kind];
+                        let distance = radius * rng.gen_range(0.0f64..1.0).sqrt();
+                        let angle = rng.gen_range(0.0..2.0 * PI);
+                        (
+                            center_x + distance * angle.cos() - size / 2.0,
+                            center_y + distance * angle.sin() - size / 2.0,
+                        )
+                    }
+                    Layout::Regions { .. } => {
+                        match regions.iter().find(|(region_id, _)| *region_id == id) {
+                            Some((_, region)) => {
+                                sample(rng, region.x, region.y, region.width, region.height, size)
+                            }
+                            None => sample(rng, 0.0, 0.0, width, height, size),
+                        }
+                    }
+                }
+            } else {
+                sample(screen.rng_mut(), 0.0, 0.0, width, height, size as f64)
+            };
+            if !(0.0..=max_x).contains(&x) || !(0.0..=max_y).contains(&y) {
+                continue;
+            }
+            let position = Position::new(x, y, max_x, max_y);
+            let probe = Object::new(position, size, size, mass, Velocity::new(0.0, 0.0));
+            if screen.is_free(&probe) {
+                placed = Some(position);
+                break;
+            }
+        }
+        match placed {
+            Some(position) => {
+                let speed = speeds
+                    .get(&id)
+                    .copied()
+                    .unwrap_or(SpeedDistribution::Fixed(speed));
+                let velocity = Velocity::random(screen.rng_mut(), &speed);
+                let mut object = Object::new(position, size, size, mass, velocity);
+                // 指定された速さを超えて操舵で切り詰められないように
+                object.set_speed_limit(velocity.magnitude());
+                screen.add_object(ScreenObject::new(id, object));
+            }
+            None => warn!("No room left to spawn object {} of {}", i + 1, count),
+        }
+    }
+    Ok(())
+}
+
+/// Top-left corner for an object of `size` lying inside the given rectangle.
+fn sample(rng: &mut impl Rng, x: f64, y: f64, width: f64, height: f64, size: f64) -> (f64, f64) {
+    let mut range = |start: f64, length: f64| {
+        if length > size {
+            rng.gen_range(start..start + length - size)
+        } else {
+            start
+        }
+    };
+    (range(x, width), range(y, height))
+}
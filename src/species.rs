@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use log::warn;
+use sdl2::pixels::Color;
+use sdl2::render::TextureCreator;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::{
+    AssetSource, Assets, TextureHandle, PAPER_SPRITE, ROCK_SPRITE, SCISSORS_SPRITE,
+};
+use crate::error::StartupError;
+use crate::json::read_json;
+use crate::shape::Shape;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SpeciesId(u8);
+
+impl SpeciesId {
+    pub const ROCK: SpeciesId = SpeciesId(0);
+    pub const PAPER: SpeciesId = SpeciesId(1);
+    pub const SCISSORS: SpeciesId = SpeciesId(2);
+
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// What a converted object does with its mass, size and speed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConversionPolicy {
+    /// Keeps the traits it spawned with.
+    #[default]
+    Preserve,
+    /// Takes on the traits of the species it was converted to.
+    Adopt,
+}
+
+/// Trait values that replace a species' defaults, e.g. from a traits file:
+/// `{"rock": {"mass": 2.0, "size": 30, "speed": 0.8}}`.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct TraitOverrides {
+    mass: Option<f64>,
+    size: Option<u32>,
+    speed: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Species {
+    name: String,
+    color: Color,
+    shape: Shape,
+    sprite: String,
+    texture: Option<TextureHandle>,
+    speed: f64,
+    mass: f64,
+    size: u32,
+    prey: Vec<SpeciesId>,
+}
+
+impl Species {
+    pub fn new(
+        name: &str,
+        color: Color,
+        shape: Shape,
+        sprite: &str,
+        speed: f64,
+        mass: f64,
+        size: u32,
+    ) -> Species {
+        assert!(mass > 0f64, "mass must be greater than 0");
+        Species {
+            name: name.to_string(),
+            color,
+            shape,
+            sprite: sprite.to_string(),
+            texture: None,
+            speed,
+            mass,
+            size,
+            prey: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    #[inline]
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    #[inline]
+    pub fn sprite(&self) -> &str {
+        &self.sprite
+    }
+
+    /// `None` when the species is drawn as its shape instead of a sprite.
+    #[inline]
+    pub fn texture(&self) -> Option<TextureHandle> {
+        self.texture
+    }
+
+    #[inline]
+    pub fn set_texture(&mut self, texture: TextureHandle) {
+        self.texture = Some(texture);
+    }
+
+    #[inline]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    #[inline]
+    pub fn mass(&self) -> f64 {
+        self.mass
+    }
+
+    #[inline]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpeciesRegistry {
+    species: Vec<Species>,
+}
+
+impl SpeciesRegistry {
+    /// Registers rock, paper and scissors in the order of the `SpeciesId` constants,
+    /// taking sprites and colours from the loaded theme where it overrides them.
+    /// A sprite that fails to load falls back to the species' shape.
+    pub fn classic<'r, T>(
+        assets: &mut Assets<'r, '_, '_>,
+        texture_creator: &'r TextureCreator<T>,
+        source: &AssetSource,
+    ) -> Result<SpeciesRegistry, StartupError> {
+        let mut registry = SpeciesRegistry::default();
+        for (name, color, shape, sprite) in [
+            ("Rock", Color::RGB(214, 126, 66), Shape::Circle, ROCK_SPRITE),
+            (
+                "Paper",
+                Color::RGB(82, 164, 255),
+                Shape::Square,
+                PAPER_SPRITE,
+            ),
+            (
+                "Scissors",
+                Color::RGB(240, 84, 128),
+                Shape::Triangle,
+                SCISSORS_SPRITE,
+            ),
+        ] {
+            let sprite = assets.theme().sprite(name, sprite);
+            let color = assets.theme().color(name, color);
+            let mut species = Species::new(name, color, shape, &sprite, 1.1, 1.0, 25);
+            if !assets.theme().shapes() {
+                match assets.load_texture(texture_creator, source, &sprite) {
+                    Ok(texture) => species.set_texture(texture),
+                    Err(e) => warn!("{}; drawing {} as a shape", e, name),
+                }
+            }
+            registry.register(species);
+        }
+        registry.add_prey(SpeciesId::ROCK, SpeciesId::SCISSORS);
+        registry.add_prey(SpeciesId::PAPER, SpeciesId::ROCK);
+        registry.add_prey(SpeciesId::SCISSORS, SpeciesId::PAPER);
+        Ok(registry)
+    }
+
+    pub fn apply_traits(&mut self, id: SpeciesId, traits: &TraitOverrides) {
+        let species = &mut self.species[id.index()];
+        if let Some(mass) = traits.mass {
+            assert!(mass > 0f64, "mass must be greater than 0");
+            species.mass = mass;
+        }
+        if let Some(size) = traits.size {
+            species.size = size;
+        }
+        if let Some(speed) = traits.speed {
+            species.speed = speed;
+        }
+    }
+
+    pub fn register(&mut self, species: Species) -> SpeciesId {
+        let id = u8::try_from(self.species.len()).expect("too many species");
+        self.species.push(species);
+        SpeciesId(id)
+    }
+
+    pub fn add_prey(&mut self, hunter: SpeciesId, prey: SpeciesId) {
+        self.species[hunter.index()].prey.push(prey);
+    }
+
+    pub fn beats(&self, hunter: SpeciesId, prey: SpeciesId) -> bool {
+        self.get(hunter).prey.contains(&prey)
+    }
+
+    #[inline]
+    pub fn get(&self, id: SpeciesId) -> &Species {
+        &self.species[id.index()]
+    }
+
+    /// Looks a species up by name, ignoring case.
+    pub fn find(&self, name: &str) -> Option<SpeciesId> {
+        self.iter()
+            .find(|(_, species)| species.name().eq_ignore_ascii_case(name))
+            .map(|(id, _)| id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SpeciesId, &Species)> {
+        self.species
+            .iter()
+            .enumerate()
+            .map(|(index, species)| (SpeciesId(index as u8), species))
+    }
+}
+
+impl TraitOverrides {
+    /// Reads a map from species name to trait overrides.
+    pub fn load(path: &Path) -> Result<HashMap<String, TraitOverrides>, String> {
+        let traits: HashMap<String, TraitOverrides> = read_json(path)?;
+        for (species, overrides) in traits.iter() {
+            overrides
+                .validate()
+                .map_err(|e| format!("{}: {}: {}", path.display(), species, e))?;
+        }
+        Ok(traits)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .mass
+            .is_some_and(|mass| !(mass > 0.0 && mass.is_finite()))
+        {
+            return Err("mass must be greater than 0".to_string());
+        }
+        if self.size == Some(0) {
+            return Err("size must be greater than 0".to_string());
+        }
+        if self
+            .speed
+            .is_some_and(|speed| !(speed >= 0.0 && speed.is_finite()))
+        {
+            return Err("speed must not be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(json: &str) -> Result<HashMap<String, TraitOverrides>, String> {
+        let path =
+            std::env::temp_dir().join(format!("traits-{}-{}.json", std::process::id(), json.len()));
+        std::fs::write(&path, json).unwrap();
+        let traits = TraitOverrides::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        traits
+    }
+
+    #[test]
+    fn loads_partial_overrides() {
+        let traits =
+            load(r#"{"rock": {"mass": 2.0}, "paper": {"size": 30, "speed": 0.0}}"#).unwrap();
+        assert_eq!(traits["rock"].mass, Some(2.0));
+        assert_eq!(traits["rock"].size, None);
+        assert_eq!(traits["paper"].size, Some(30));
+    }
+
+    #[test]
+    fn rejects_values_that_would_break_the_simulation() {
+        assert!(load(r#"{"rock": {"mass": 0.0}}"#).is_err());
+        assert!(load(r#"{"rock": {"mass": -1.5}}"#).is_err());
+        assert!(load(r#"{"rock": {"size": 0}}"#).is_err());
+        assert!(load(r#"{"rock": {"speed": -0.5}}"#).is_err());
+    }
+}
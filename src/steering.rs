@@ -0,0 +1,178 @@
+use crate::behavior::{Behavior, Neighbors};
+use crate::screen_object::ScreenObject;
+
+/// Accelerates toward the nearest prey and away from the nearest predator.
+#[derive(Debug, Clone, Copy)]
+pub struct ChaseFlee {
+    perception: f64,
+    max_acceleration: f64,
+}
+
+/// Ignores prey and only runs from predators.
+#[derive(Debug, Clone, Copy)]
+pub struct Coward {
+    perception: f64,
+    max_acceleration: f64,
+}
+
+/// Moves toward the centre of nearby members of its own species while
+/// running from predators.
+#[derive(Debug, Clone, Copy)]
+pub struct Swarm {
+    perception: f64,
+    max_acceleration: f64,
+}
+
+impl ChaseFlee {
+    pub fn new(perception: f64, max_acceleration: f64) -> Result<ChaseFlee, String> {
+        check(perception, max_acceleration)?;
+        Ok(ChaseFlee {
+            perception,
+            max_acceleration,
+        })
+    }
+}
+
+impl Coward {
+    pub fn new(perception: f64, max_acceleration: f64) -> Result<Coward, String> {
+        check(perception, max_acceleration)?;
+        Ok(Coward {
+            perception,
+            max_acceleration,
+        })
+    }
+}
+
+impl Swarm {
+    pub fn new(perception: f64, max_acceleration: f64) -> Result<Swarm, String> {
+        check(perception, max_acceleration)?;
+        Ok(Swarm {
+            perception,
+            max_acceleration,
+        })
+    }
+}
+
+impl Behavior for ChaseFlee {
+    fn acceleration(&self, object: &ScreenObject, neighbors: &Neighbors) -> (f64, f64) {
+        let registry = neighbors.species();
+        let prey = neighbors.nearest(self.perception, |other| {
+            registry.beats(object.species(), other.species())
+        });
+        let (x, y) = away_from_predator(object, neighbors, self.perception);
+        let (px, py) = prey.map_or((0.0, 0.0), unit);
+        limit((x + px, y + py), self.max_acceleration)
+    }
+
+    #[inline]
+    fn perception(&self) -> f64 {
+        self.perception
+    }
+}
+
+impl Behavior for Coward {
+    fn acceleration(&self, object: &ScreenObject, neighbors: &Neighbors) -> (f64, f64) {
+        let direction = away_from_predator(object, neighbors, self.perception);
+        limit(direction, self.max_acceleration)
+    }
+
+    #[inline]
+    fn perception(&self) -> f64 {
+        self.perception
+    }
+}
+
+impl Behavior for Swarm {
+    fn acceleration(&self, object: &ScreenObject, neighbors: &Neighbors) -> (f64, f64) {
+        let (mut sum_x, mut sum_y, mut count) = (0.0, 0.0, 0);
+        for (_, (dx, dy)) in neighbors
+            .within(self.perception)
+            .filter(|(other, _)| other.species() == object.species())
+        {
+            sum_x += dx;
+            sum_y += dy;
+            count += 1;
+        }
+        let (cx, cy) = if count > 0 {
+            unit((sum_x / count as f64, sum_y / count as f64))
+        } else {
+            (0.0, 0.0)
+        };
+        let (x, y) = away_from_predator(object, neighbors, self.perception);
+        limit((x + cx, y + cy), self.max_acceleration)
+    }
+
+    #[inline]
+    fn perception(&self) -> f64 {
+        self.perception
+    }
+}
+
+/// Looks up a built-in behaviour by the name used on the command line.
+/// `Ok(None)` means there is no behaviour with that name.
+pub fn by_name(
+    name: &str,
+    perception: f64,
+    max_acceleration: f64,
+) -> Result<Option<Box<dyn Behavior + Send>>, String> {
+    Ok(match name {
+        "chase" => Some(Box::new(ChaseFlee::new(perception, max_acceleration)?)),
+        "coward" => Some(Box::new(Coward::new(perception, max_acceleration)?)),
+        "swarm" => Some(Box::new(Swarm::new(perception, max_acceleration)?)),
+        _ => None,
+    })
+}
+
+fn check(perception: f64, max_acceleration: f64) -> Result<(), String> {
+    // 知覚半径は近傍グリッドのセルの大きさにもなるので、有限の正の値に限る
+    if !(perception > 0.0 && perception.is_finite()) {
+        return Err(format!(
+            "perception must be greater than 0, got {}",
+            perception
+        ));
+    }
+    if !(max_acceleration >= 0.0 && max_acceleration.is_finite()) {
+        return Err(format!(
+            "max acceleration must not be negative, got {}",
+            max_acceleration
+        ));
+    }
+    Ok(())
+}
+
+fn away_from_predator(object: &ScreenObject, neighbors: &Neighbors, perception: f64) -> (f64, f64) {
+    let registry = neighbors.species();
+    let predator = neighbors.nearest(perception, |other| {
+        registry.beats(other.species(), object.species())
+    });
+    predator.map_or((0.0, 0.0), |(dx, dy)| unit((-dx, -dy)))
+}
+
+fn unit((x, y): (f64, f64)) -> (f64, f64) {
+    let length = x.hypot(y);
+    if length <= f64::EPSILON {
+        (0.0, 0.0)
+    } else {
+        (x / length, y / length)
+    }
+}
+
+fn limit(direction: (f64, f64), max: f64) -> (f64, f64) {
+    let (x, y) = unit(direction);
+    (x * max, y * max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_perception_and_acceleration_out_of_range() {
+        assert!(ChaseFlee::new(100.0, 0.1).is_ok());
+        assert!(Coward::new(0.0, 0.1).is_err());
+        assert!(Swarm::new(f64::INFINITY, 0.1).is_err());
+        assert!(ChaseFlee::new(100.0, -0.1).is_err());
+        assert!(by_name("swarm", 100.0, f64::NAN).is_err());
+        assert!(by_name("dance", 100.0, 0.1).unwrap().is_none());
+    }
+}
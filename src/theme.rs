@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use sdl2::pixels::Color;
+use serde::Deserialize;
+
+use crate::assets::FONT_FILE;
+use crate::json::read_json;
+
+pub const MANIFEST_FILE: &str = "theme.json";
+
+/// Visual settings read from a theme directory's `theme.json`. Anything the
+/// manifest leaves out falls back to the classic look.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    background: [u8; 3],
+    text_color: [u8; 3],
+    obstacle_color: [u8; 3],
+    font: String,
+    font_size: u16,
+    shapes: bool,
+    species: HashMap<String, SpeciesStyle>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SpeciesStyle {
+    sprite: Option<String>,
+    color: Option<[u8; 3]>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: [0, 0, 0],
+            text_color: [255, 255, 255],
+            obstacle_color: [110, 110, 120],
+            font: FONT_FILE.to_string(),
+            font_size: 40,
+            shapes: false,
+            species: HashMap::new(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn load(dir: &Path) -> Result<Theme, String> {
+        let mut theme: Theme = read_json(&dir.join(MANIFEST_FILE))?;
+        // 種族名は大文字小文字を区別しない
+        theme.species = theme
+            .species
+            .into_iter()
+            .map(|(name, style)| (name.to_lowercase(), style))
+            .collect();
+        Ok(theme)
+    }
+
+    #[inline]
+    pub fn background(&self) -> Color {
+        let [r, g, b] = self.background;
+        Color::RGB(r, g, b)
+    }
+
+    #[inline]
+    pub fn text_color(&self) -> Color {
+        let [r, g, b] = self.text_color;
+        Color::RGB(r, g, b)
+    }
+
+    #[inline]
+    pub fn obstacle_color(&self) -> Color {
+        let [r, g, b] = self.obstacle_color;
+        Color::RGB(r, g, b)
+    }
+
+    #[inline]
+    pub fn font(&self) -> &str {
+        &self.font
+    }
+
+    #[inline]
+    pub fn font_size(&self) -> u16 {
+        self.font_size
+    }
+
+    /// Draws every species as a coloured shape instead of loading sprites.
+    #[inline]
+    pub fn shapes(&self) -> bool {
+        self.shapes
+    }
+
+    #[inline]
+    pub fn set_shapes(&mut self, shapes: bool) {
+        self.shapes = shapes;
+    }
+
+    pub fn sprite(&self, species: &str, default: &str) -> String {
+        self.style(species)
+            .and_then(|style| style.sprite.clone())
+            .unwrap_or_else(|| default.to_string())
+    }
+
+    pub fn color(&self, species: &str, default: Color) -> Color {
+        match self.style(species).and_then(|style| style.color) {
+            Some([r, g, b]) => Color::RGB(r, g, b),
+            None => default,
+        }
+    }
+
+    fn style(&self, species: &str) -> Option<&SpeciesStyle> {
+        self.species.get(&species.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn species_names_in_the_manifest_are_case_insensitive() {
+        let dir = std::env::temp_dir().join(format!("theme-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(MANIFEST_FILE),
+            r#"{"species": {"Rock": {"sprite": "boulder.png", "color": [1, 2, 3]}}}"#,
+        )
+        .unwrap();
+        let theme = Theme::load(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let theme = theme.unwrap();
+        assert_eq!(theme.sprite("rock", "rock.png"), "boulder.png");
+        assert_eq!(
+            theme.color("ROCK", Color::RGB(0, 0, 0)),
+            Color::RGB(1, 2, 3)
+        );
+        assert_eq!(theme.sprite("paper", "paper.png"), "paper.png");
+    }
+}
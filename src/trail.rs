@@ -0,0 +1,62 @@
+use std::collections::VecDeque;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
+use sdl2::render::{BlendMode, Canvas, RenderTarget};
+
+use crate::camera::Camera;
+
+const TRAIL_LENGTH: usize = 120;
+/// Longer steps are teleports (wrapping, restoring) and are not drawn.
+const MAX_SEGMENT: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy)]
+struct TrailPoint {
+    x: f64,
+    y: f64,
+    color: Color,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Trail {
+    points: VecDeque<TrailPoint>,
+}
+
+impl Trail {
+    pub fn push(&mut self, (x, y): (f64, f64), color: Color) {
+        if self.points.len() == TRAIL_LENGTH {
+            self.points.pop_front();
+        }
+        self.points.push_back(TrailPoint { x, y, color });
+    }
+
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, camera: &Camera) {
+        canvas.set_blend_mode(BlendMode::Blend);
+        let len = self.points.len();
+        for (i, (from, to)) in self
+            .points
+            .iter()
+            .zip(self.points.iter().skip(1))
+            .enumerate()
+        {
+            if (to.x - from.x).hypot(to.y - from.y) > MAX_SEGMENT {
+                continue;
+            }
+            // 古い点ほど薄く、線の色は新しい方の点の種族に合わせる
+            let color = to.color;
+            let alpha = (255 * (i + 1) / len) as u8;
+            canvas.set_draw_color(Color::RGBA(color.r, color.g, color.b, alpha));
+            let ((from_x, from_y), (to_x, to_y)) = (
+                camera.to_screen((from.x, from.y)),
+                camera.to_screen((to.x, to.y)),
+            );
+            canvas
+                .draw_line(
+                    Point::new(from_x as i32, from_y as i32),
+                    Point::new(to_x as i32, to_y as i32),
+                )
+                .expect("failed to draw trail");
+        }
+        canvas.set_blend_mode(BlendMode::None);
+    }
+}
@@ -1,4 +1,9 @@
-#[derive(Debug, Clone, Copy)]
+use std::f64::consts::PI;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Velocity {
     x: f64,
     y: f64,
@@ -9,10 +14,11 @@ impl Velocity {
         Velocity { x, y }
     }
 
-    pub fn random(max_x: f64, max_y: f64) -> Velocity {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        Self::new(rng.gen_range(0.5..max_x), rng.gen_range(0.5..max_y))
+    /// Direction uniform on the circle, speed drawn from `speed`.
+    pub fn random(rng: &mut impl Rng, speed: &SpeedDistribution) -> Velocity {
+        let angle = rng.gen_range(0.0..2.0 * PI);
+        let speed = speed.sample(rng);
+        Self::new(speed * angle.cos(), speed * angle.sin())
     }
 
     #[inline]
@@ -25,6 +31,46 @@ impl Velocity {
         self.y
     }
 
+    pub fn magnitude(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// Keeps the direction but changes the speed to `magnitude`.
+    pub fn set_magnitude(&mut self, magnitude: f64) {
+        let current = self.magnitude();
+        if current > f64::EPSILON {
+            self.scale(magnitude / current);
+        }
+    }
+
+    /// Scales the velocity down so its magnitude is at most `max`.
+    pub fn limit(&mut self, max: f64) {
+        let magnitude = self.magnitude();
+        if magnitude > max {
+            self.scale(max / magnitude);
+        }
+    }
+
+    pub fn accelerate(&mut self, x: f64, y: f64) {
+        self.x += x;
+        self.y += y;
+    }
+
+    pub fn scale(&mut self, factor: f64) {
+        self.x *= factor;
+        self.y *= factor;
+    }
+
+    /// Mirrors the velocity about a surface with unit normal `(nx, ny)` if it
+    /// is heading into that surface.
+    pub fn reflect(&mut self, nx: f64, ny: f64) {
+        let dot = self.x * nx + self.y * ny;
+        if dot < 0.0 {
+            self.x -= 2.0 * dot * nx;
+            self.y -= 2.0 * dot * ny;
+        }
+    }
+
     pub fn reverse_x(&mut self) {
         self.x = -self.x;
     }
@@ -33,3 +79,123 @@ impl Velocity {
         self.y = -self.y;
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpeedDistribution {
+    Fixed(f64),
+    Uniform { min: f64, max: f64 },
+    Normal { mean: f64, std_dev: f64 },
+}
+
+impl SpeedDistribution {
+    /// Parses `fixed:1.1`, `uniform:0.5,1.5` or `normal:1.1,0.2`.
+    pub fn parse(text: &str) -> Option<SpeedDistribution> {
+        let (kind, values) = text.split_once(':')?;
+        let values = values
+            .split(',')
+            .map(|value| value.trim().parse::<f64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let distribution = match (kind, values.as_slice()) {
+            ("fixed", &[speed]) => SpeedDistribution::Fixed(speed),
+            ("uniform", &[min, max]) if min < max => SpeedDistribution::Uniform { min, max },
+            ("normal", &[mean, std_dev]) => SpeedDistribution::Normal { mean, std_dev },
+            _ => return None,
+        };
+        Some(distribution)
+    }
+
+    /// Never negative; a normal sample below zero is clamped.
+    pub fn sample(&self, rng: &mut impl Rng) -> f64 {
+        match *self {
+            SpeedDistribution::Fixed(speed) => speed,
+            SpeedDistribution::Uniform { min, max } => rng.gen_range(min..max),
+            SpeedDistribution::Normal { mean, std_dev } => {
+                // Box-Muller 法
+                let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+                let u2: f64 = rng.gen_range(0.0..1.0);
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+                mean + std_dev * z
+            }
+        }
+        .max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    use super::*;
+
+    #[test]
+    fn reflect_mirrors_only_when_heading_into_the_surface() {
+        let mut velocity = Velocity::new(1.0, 2.0);
+        velocity.reflect(-1.0, 0.0);
+        assert_eq!((velocity.x(), velocity.y()), (-1.0, 2.0));
+
+        // 離れていく向きならそのまま
+        velocity.reflect(-1.0, 0.0);
+        assert_eq!((velocity.x(), velocity.y()), (-1.0, 2.0));
+
+        let normal = std::f64::consts::FRAC_1_SQRT_2;
+        let mut velocity = Velocity::new(1.0, 0.0);
+        velocity.reflect(-normal, -normal);
+        assert!(velocity.x().abs() < 1e-9 && (velocity.y() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parses_speed_distributions() {
+        assert_eq!(
+            SpeedDistribution::parse("fixed:1.1"),
+            Some(SpeedDistribution::Fixed(1.1))
+        );
+        assert_eq!(
+            SpeedDistribution::parse("uniform:0.5, 1.5"),
+            Some(SpeedDistribution::Uniform { min: 0.5, max: 1.5 })
+        );
+        assert_eq!(
+            SpeedDistribution::parse("normal:1.1,0.2"),
+            Some(SpeedDistribution::Normal {
+                mean: 1.1,
+                std_dev: 0.2
+            })
+        );
+        for text in [
+            "1.1",
+            "fixed:",
+            "fixed:1,2",
+            "uniform:2,1",
+            "normal:1",
+            "gamma:1,2",
+        ] {
+            assert_eq!(SpeedDistribution::parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn samples_stay_in_range() {
+        let mut rng = Pcg64::seed_from_u64(0);
+        let uniform = SpeedDistribution::Uniform { min: 0.5, max: 1.5 };
+        let normal = SpeedDistribution::Normal {
+            mean: 0.1,
+            std_dev: 1.0,
+        };
+        for _ in 0..1000 {
+            assert!((0.5..1.5).contains(&uniform.sample(&mut rng)));
+            assert!(normal.sample(&mut rng) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn random_velocity_has_the_sampled_speed_in_any_direction() {
+        let mut rng = Pcg64::seed_from_u64(0);
+        let mut quadrants = [false; 4];
+        for _ in 0..100 {
+            let velocity = Velocity::random(&mut rng, &SpeedDistribution::Fixed(1.1));
+            assert!((velocity.magnitude() - 1.1).abs() < 1e-9);
+            quadrants[(velocity.x() < 0.0) as usize * 2 + (velocity.y() < 0.0) as usize] = true;
+        }
+        assert_eq!(quadrants, [true; 4]);
+    }
+}
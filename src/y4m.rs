@@ -0,0 +1,102 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::frame::{Frame, FrameSink};
+
+/// Streams frames into an uncompressed YUV4MPEG2 (4:4:4) file.
+pub struct Y4mWriter {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    frame_rate: (u32, u32),
+    size: Option<(u32, u32)>,
+    planes: Vec<u8>,
+}
+
+impl Y4mWriter {
+    pub fn new(path: PathBuf, frame_rate: (u32, u32)) -> Result<Y4mWriter, String> {
+        let file = File::create(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Y4mWriter {
+            path,
+            writer: BufWriter::new(file),
+            frame_rate,
+            size: None,
+            planes: Vec::new(),
+        })
+    }
+
+    fn write_header(&mut self, width: u32, height: u32) -> std::io::Result<()> {
+        writeln!(
+            self.writer,
+            "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C444",
+            width, height, self.frame_rate.0, self.frame_rate.1
+        )
+    }
+
+    fn write_planes(&mut self, frame: &Frame) -> std::io::Result<()> {
+        let pixel_count = (frame.width() * frame.height()) as usize;
+        self.planes.resize(pixel_count * 3, 0);
+        let (y_plane, chroma) = self.planes.split_at_mut(pixel_count);
+        let (u_plane, v_plane) = chroma.split_at_mut(pixel_count);
+        for (i, rgb) in frame.pixels().chunks_exact(3).enumerate() {
+            (y_plane[i], u_plane[i], v_plane[i]) = to_yuv(rgb[0], rgb[1], rgb[2]);
+        }
+        self.writer.write_all(b"FRAME\n")?;
+        self.writer.write_all(&self.planes)
+    }
+}
+
+impl FrameSink for Y4mWriter {
+    fn write_frame(&mut self, frame: &Frame) -> Result<(), String> {
+        match self.size {
+            None => {
+                self.write_header(frame.width(), frame.height())
+                    .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+                self.size = Some((frame.width(), frame.height()));
+            }
+            Some(size) if size != (frame.width(), frame.height()) => {
+                return Err(format!(
+                    "{}: frame size changed from {}x{} to {}x{}",
+                    self.path.display(),
+                    size.0,
+                    size.1,
+                    frame.width(),
+                    frame.height()
+                ));
+            }
+            Some(_) => {}
+        }
+        self.write_planes(frame)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.writer
+            .flush()
+            .map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+}
+
+/// BT.601 limited range
+fn to_yuv(r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    (
+        (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8,
+        (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8,
+        (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_to_limited_range_bt601() {
+        assert_eq!(to_yuv(0, 0, 0), (16, 128, 128));
+        assert_eq!(to_yuv(255, 255, 255), (235, 128, 128));
+        assert_eq!(to_yuv(255, 0, 0), (82, 90, 240));
+        assert_eq!(to_yuv(0, 255, 0), (144, 54, 34));
+        assert_eq!(to_yuv(0, 0, 255), (41, 240, 110));
+    }
+}